@@ -0,0 +1,53 @@
+mod simd_0185;
+mod simd_0321;
+mod simd_0387;
+
+use helpers::{args::GLOBAL_OPTIONS_USAGE, CommandArgs, GlobalArgs};
+
+fn usage(program_name: &str) -> ! {
+    eprintln!("Usage: {} <simd> <command> [args] [options]", program_name);
+    eprintln!();
+    eprintln!("Commands:");
+    eprintln!("  0185 create                  create a v4 vote account via CPI and view it");
+    eprintln!("  0185 view <vote_account>     log a v4 vote account's fields");
+    eprintln!("  0185 stake <vote_account>    delegate a fresh stake account to a vote account");
+    eprintln!("  0185 sweep                   delegate to a sample of fetched vote accounts");
+    eprintln!("        [--file <path>] [--count <n>] [--concurrency <n>]");
+    eprintln!("  0185 close [<withdrawer_keypair>]");
+    eprintln!("                               withdraw everything from vote accounts it created");
+    eprintln!("                               (default: the latest saved withdrawer)");
+    eprintln!("  0321 send [--hex <bytes>]    send raw bytes (default: 0xDEADBEEF + EasterEgg)");
+    eprintln!("  0387 set [<vote_account> <authorized_voter_keypair> | latest]");
+    eprintln!("                               set a BLS pubkey on a new, existing, or the latest");
+    eprintln!("                               saved v4 account");
+    eprintln!("  0387 view <vote_account>     log a v4 vote account's BLS pubkey");
+    eprintln!();
+    eprintln!("{GLOBAL_OPTIONS_USAGE}");
+    std::process::exit(1);
+}
+
+/// Parse a command's arguments against the options it takes, or print why
+/// they are invalid.
+fn command_args(args: &[String], value_flags: &[&str], switches: &[&str]) -> Option<CommandArgs> {
+    CommandArgs::parse(args, value_flags, switches)
+        .inspect_err(|err| eprintln!("Error: {err}"))
+        .ok()
+}
+
+fn main() {
+    let global = GlobalArgs::parse();
+
+    // The SIMD and command come first, then the command's own arguments.
+    let ok = match &global.rest[..] {
+        [simd, command, args @ ..] if !command.starts_with("--") => match simd.as_str() {
+            "0185" => simd_0185::run(&global, command, args),
+            "0321" => simd_0321::run(&global, command, args),
+            "0387" => simd_0387::run(&global, command, args),
+            _ => false,
+        },
+        _ => false,
+    };
+    if !ok {
+        usage(&global.program_name);
+    }
+}
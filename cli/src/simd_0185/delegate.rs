@@ -0,0 +1,59 @@
+//! Stake delegation shared by `stake` and `sweep`.
+
+use {
+    helpers::{Hash, Keypair, Pubkey, RpcClient, Signer, Transaction},
+    solana_stake_interface::{
+        instruction::{delegate_stake, initialize},
+        state::{Authorized, Lockup, StakeStateV2},
+    },
+    solana_system_interface::instruction::create_account,
+};
+
+pub const STAKE_STATE_SIZE: usize = std::mem::size_of::<StakeStateV2>();
+
+/// Stake amount: 5,000 lamports.
+pub const STAKE_AMOUNT: u64 = 5_000;
+
+pub fn stake_account_rent(client: &RpcClient) -> u64 {
+    client
+        .get_minimum_balance_for_rent_exemption(STAKE_STATE_SIZE)
+        .expect("failed to get rent exemption")
+}
+
+/// Create a stake account funded with `total_lamports`, initialize it with
+/// the payer as staker and withdrawer, and delegate it to `vote_account`.
+pub fn delegate_stake_transaction(
+    payer: &Keypair,
+    stake_account: &Keypair,
+    vote_account: &Pubkey,
+    total_lamports: u64,
+    blockhash: Hash,
+) -> Transaction {
+    // Step 1: Create stake account.
+    let create_account_ix = create_account(
+        &payer.pubkey(),
+        &stake_account.pubkey(),
+        total_lamports,
+        STAKE_STATE_SIZE as u64,
+        &solana_sdk_ids::stake::ID,
+    );
+
+    // Step 2: Initialize stake account.
+    let authorized = Authorized {
+        staker: payer.pubkey(),
+        withdrawer: payer.pubkey(),
+    };
+    let lockup = Lockup::default();
+
+    let initialize_ix = initialize(&stake_account.pubkey(), &authorized, &lockup);
+
+    // Step 3: Delegate stake.
+    let delegate_ix = delegate_stake(&stake_account.pubkey(), &payer.pubkey(), vote_account);
+
+    Transaction::new_signed_with_payer(
+        &[create_account_ix, initialize_ix, delegate_ix],
+        Some(&payer.pubkey()),
+        &[payer, stake_account],
+        blockhash,
+    )
+}
@@ -0,0 +1,294 @@
+//! SIMD-0185: Vote State V4.
+
+mod delegate;
+mod sweep;
+
+use {
+    delegate::{delegate_stake_transaction, stake_account_rent, STAKE_AMOUNT},
+    helpers::{
+        keystore::{Keystore, Role},
+        read_keypair_file,
+        vote::{fetch_vote_accounts, VoteAccountFilter, VoteStateVersion},
+        GlobalArgs, Keypair, RpcClient, Signer, Transaction,
+    },
+    simd_0185_interface::{get_identity_pda, ProgramInstruction},
+    solana_pubkey::Pubkey,
+    std::str::FromStr,
+};
+
+/// The feature exercised by this client.
+const FEATURE_ID: Pubkey = agave_feature_set::vote_state_v4::ID;
+
+const PROGRAM: &str = "simd-0185";
+
+pub fn run(global: &GlobalArgs, command: &str, args: &[String]) -> bool {
+    let value_flags: &[&str] = match command {
+        "sweep" => &["--file", "--count", "--concurrency"],
+        _ => &[],
+    };
+    let Some(args) = crate::command_args(args, value_flags, &[]) else {
+        return false;
+    };
+
+    match (command, &args.positional()[..]) {
+        ("create", []) => create(global),
+        ("view", [vote_account]) => view(global, &parse_vote_account(vote_account)),
+        ("stake", [vote_account]) => stake(global, &parse_vote_account(vote_account)),
+        ("sweep", []) => sweep::sweep(global, &args),
+        ("close", []) => close(global, None),
+        ("close", [keypair]) => close(global, Some(keypair)),
+        _ => return false,
+    }
+    true
+}
+
+fn parse_vote_account(vote_account: &str) -> Pubkey {
+    Pubkey::from_str(vote_account).expect("Invalid vote account pubkey")
+}
+
+/// Exit with `err` if the keystore couldn't be read or written.
+fn or_exit<T>(result: Result<T, String>) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        std::process::exit(1);
+    })
+}
+
+/// Create a v4 vote account via CPI and view it, in one transaction.
+fn create(global: &GlobalArgs) {
+    let (client, payer) = helpers::client_with_network_override(global, None);
+    helpers::feature::require_feature(&client, &FEATURE_ID, global.force);
+
+    let program_id = helpers::program_id(global, &client, PROGRAM);
+
+    // Generate a fresh keypair for the vote account.
+    let vote_account = Keypair::new();
+    let authorized_voter = Keypair::new();
+    let authorized_withdrawer = Keypair::new();
+    let commission = 10;
+
+    helpers::status!(global, "Payer:                  {}", payer.pubkey());
+    helpers::status!(global, "Vote account:           {}", vote_account.pubkey());
+    helpers::status!(
+        global,
+        "Authorized voter:       {}",
+        authorized_voter.pubkey()
+    );
+    helpers::status!(
+        global,
+        "Authorized withdrawer:  {}",
+        authorized_withdrawer.pubkey()
+    );
+    helpers::status!(global, "Commission:             {}%", commission);
+
+    helpers::status!(global);
+
+    // Create instruction.
+    let create_ix = ProgramInstruction::create(
+        &program_id,
+        &payer.pubkey(),
+        &vote_account.pubkey(),
+        &authorized_voter.pubkey(),
+        &authorized_withdrawer.pubkey(),
+        commission,
+    );
+
+    // View instruction.
+    let view_ix = ProgramInstruction::view(&program_id, &vote_account.pubkey());
+
+    // Build, sign, and send the transaction.
+    let blockhash = client
+        .get_latest_blockhash()
+        .expect("failed to get blockhash");
+    let tx = Transaction::new_signed_with_payer(
+        &[create_ix, view_ix],
+        Some(&payer.pubkey()),
+        &[&payer, &vote_account],
+        blockhash,
+    );
+
+    // Save the keypairs before sending, so they aren't lost if the account
+    // gets created but the client fails waiting for confirmation. They let
+    // the account be reused and its rent reclaimed with `close`.
+    let run = (!global.simulate).then(|| {
+        let network = helpers::network_name(&client.url());
+        let mut run = or_exit(Keystore::default().create_run(&network, "0185 create"));
+        for (role, keypair) in [
+            (Role::VoteAccount, &vote_account),
+            (Role::AuthorizedVoter, &authorized_voter),
+            (Role::AuthorizedWithdrawer, &authorized_withdrawer),
+        ] {
+            or_exit(run.save(role, keypair));
+        }
+        helpers::status!(global, "Keypairs saved to:      {}", run.dir().display());
+        helpers::status!(global);
+        run
+    });
+
+    let report = helpers::execute_transaction(global, &client, &tx);
+    helpers::status!(global);
+    report.print(global.output);
+    if !report.is_success() {
+        // The transaction was rejected or failed, so no account exists.
+        if let Some(run) = run {
+            or_exit(run.remove());
+        }
+        std::process::exit(1);
+    }
+}
+
+/// Log the fields of an existing v4 vote account.
+fn view(global: &GlobalArgs, vote_account: &Pubkey) {
+    let (client, payer) = helpers::client_with_network_override(global, None);
+    helpers::feature::require_feature(&client, &FEATURE_ID, global.force);
+
+    let program_id = helpers::program_id(global, &client, PROGRAM);
+
+    helpers::status!(global, "Vote account:           {}", vote_account);
+    helpers::status!(global);
+
+    let view_ix = ProgramInstruction::view(&program_id, vote_account);
+
+    let blockhash = client
+        .get_latest_blockhash()
+        .expect("failed to get blockhash");
+    let tx =
+        Transaction::new_signed_with_payer(&[view_ix], Some(&payer.pubkey()), &[&payer], blockhash);
+
+    helpers::send_transaction(global, &client, &tx);
+}
+
+/// Create a stake account, initialize it, and delegate it to `vote_account`.
+fn stake(global: &GlobalArgs, vote_account: &Pubkey) {
+    let (client, payer) = helpers::client_with_network_override(global, None);
+
+    // Generate a fresh keypair for the stake account
+    let stake_account = Keypair::new();
+
+    let stake_amount = STAKE_AMOUNT;
+
+    // Get rent for stake account
+    let rent = stake_account_rent(&client);
+
+    let total_lamports = rent + stake_amount;
+
+    helpers::status!(global, "Payer:           {}", payer.pubkey());
+    helpers::status!(global, "Stake account:   {}", stake_account.pubkey());
+    helpers::status!(global, "Vote account:    {}", vote_account);
+    helpers::status!(global, "Stake amount:    {} lamports", stake_amount);
+    helpers::status!(global, "Rent exemption:  {} lamports", rent);
+    helpers::status!(global, "Total lamports:  {} lamports", total_lamports);
+    helpers::status!(global);
+
+    // Build, sign, and send the transaction.
+    let blockhash = client
+        .get_latest_blockhash()
+        .expect("failed to get blockhash");
+    let tx = delegate_stake_transaction(
+        &payer,
+        &stake_account,
+        vote_account,
+        total_lamports,
+        blockhash,
+    );
+
+    helpers::send_transaction(global, &client, &tx);
+}
+
+/// The authorized withdrawer keypair at `keypair_path`, or else the one the
+/// latest `create` run on `client`'s network saved.
+fn load_authorized_withdrawer(
+    global: &GlobalArgs,
+    client: &RpcClient,
+    keypair_path: Option<&str>,
+) -> Keypair {
+    if let Some(keypair_path) = keypair_path {
+        return read_keypair_file(keypair_path)
+            .expect("failed to read authorized withdrawer keypair");
+    }
+
+    let network = helpers::network_name(&client.url());
+    let Some(run) = or_exit(Keystore::default().latest(&network, &[Role::AuthorizedWithdrawer]))
+    else {
+        eprintln!("Error: no saved authorized withdrawer for {network}; pass a keypair");
+        std::process::exit(1);
+    };
+    helpers::status!(global, "Keypairs loaded from: {}", run.dir().display());
+    or_exit(run.keypair(Role::AuthorizedWithdrawer))
+}
+
+/// Withdraw all lamports back to the payer from every vote account the
+/// program created with the authorized withdrawer at `keypair_path`, or the
+/// latest saved one, one transaction per account.
+fn close(global: &GlobalArgs, keypair_path: Option<&str>) {
+    let (client, payer) = helpers::client_with_network_override(global, None);
+
+    let program_id = helpers::program_id(global, &client, PROGRAM);
+    let authorized_withdrawer = load_authorized_withdrawer(global, &client, keypair_path);
+
+    // The program makes its identity PDA the node of every account it
+    // creates. Accounts made with `--force` before SIMD-0185 activated are
+    // still v3, so look for both layouts.
+    let (identity_pda, _) = get_identity_pda(&program_id);
+    let mut vote_accounts = Vec::new();
+    for version in [VoteStateVersion::V3, VoteStateVersion::V4] {
+        let filter = VoteAccountFilter {
+            version,
+            node_pubkey: Some(identity_pda),
+            authorized_withdrawer: Some(authorized_withdrawer.pubkey()),
+            ..VoteAccountFilter::default()
+        };
+        vote_accounts.extend(or_exit(fetch_vote_accounts(&client, &filter)));
+    }
+
+    helpers::status!(global, "Payer:                  {}", payer.pubkey());
+    helpers::status!(
+        global,
+        "Authorized withdrawer:  {}",
+        authorized_withdrawer.pubkey()
+    );
+    helpers::status!(global, "Vote accounts:          {}", vote_accounts.len());
+    helpers::status!(global);
+
+    let mut failed = 0;
+    for vote_account in &vote_accounts {
+        let vote_pubkey = parse_vote_account(&vote_account.pubkey);
+        helpers::status!(
+            global,
+            "Closing {} ({} lamports)",
+            vote_pubkey,
+            vote_account.lamports
+        );
+
+        let withdraw_ix = ProgramInstruction::withdraw(
+            &program_id,
+            &vote_pubkey,
+            &payer.pubkey(),
+            &authorized_withdrawer.pubkey(),
+        );
+        let blockhash = client
+            .get_latest_blockhash()
+            .expect("failed to get blockhash");
+        let tx = Transaction::new_signed_with_payer(
+            &[withdraw_ix],
+            Some(&payer.pubkey()),
+            &[&payer, &authorized_withdrawer],
+            blockhash,
+        );
+
+        let report = helpers::execute_transaction(global, &client, &tx);
+        helpers::status!(global);
+        report.print(global.output);
+        if !report.is_success() {
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        eprintln!(
+            "Error: {failed} of {} withdrawals failed",
+            vote_accounts.len()
+        );
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,256 @@
+//! Delegate to a random sample of fetched vote accounts in parallel.
+
+use {
+    super::{
+        delegate::{delegate_stake_transaction, stake_account_rent, STAKE_AMOUNT},
+        FEATURE_ID,
+    },
+    helpers::{
+        feature::get_feature_status,
+        vote::{ListingFormat, VoteStateVersion},
+        CommandArgs, GlobalArgs, InstructionError, Keypair, OutputFormat, Pubkey, RpcClient,
+        TransactionError,
+    },
+    rand::seq::SliceRandom,
+    serde::Serialize,
+    solana_program_error::ProgramError,
+    solana_stake_interface::error::StakeError,
+    std::{collections::BTreeMap, fmt, path::PathBuf, str::FromStr, sync::Mutex},
+};
+
+/// Index of the `DelegateStake` instruction in the delegation transaction.
+const DELEGATE_INSTRUCTION_INDEX: u8 = 2;
+
+/// Why a delegation to a vote account didn't land.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ErrorCategory {
+    InvalidPubkey,
+    VoteAccountNotFound,
+    NotVoteAccount,
+    FeatureInactive,
+    InsufficientStake,
+    InsufficientFunds,
+    TransactionFailed,
+    Rpc,
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::InvalidPubkey => "invalid pubkey",
+            Self::VoteAccountNotFound => "vote account not found",
+            Self::NotVoteAccount => "not a vote account",
+            Self::FeatureInactive => "feature inactive",
+            Self::InsufficientStake => "insufficient stake",
+            Self::InsufficientFunds => "insufficient funds",
+            Self::TransactionFailed => "transaction failed",
+            Self::Rpc => "rpc error",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum Outcome {
+    Delegated {
+        signature: String,
+        simulated: bool,
+    },
+    Failed {
+        category: ErrorCategory,
+        detail: String,
+    },
+}
+
+impl Outcome {
+    fn failed(category: ErrorCategory, detail: impl ToString) -> Self {
+        Self::Failed {
+            category,
+            detail: detail.to_string(),
+        }
+    }
+}
+
+/// Map a failed delegation transaction's error to a category.
+fn categorize(err: &TransactionError) -> ErrorCategory {
+    let is_stake_error = |code: &u32, errors: &[StakeError]| {
+        errors
+            .iter()
+            .any(|err| ProgramError::from(err.clone()) == ProgramError::Custom(*code))
+    };
+    match err {
+        TransactionError::InsufficientFundsForFee
+        | TransactionError::InsufficientFundsForRent { .. }
+        | TransactionError::InstructionError(0, InstructionError::Custom(1)) => {
+            ErrorCategory::InsufficientFunds
+        }
+        TransactionError::InstructionError(
+            DELEGATE_INSTRUCTION_INDEX,
+            InstructionError::Custom(code),
+        ) if is_stake_error(
+            code,
+            &[
+                StakeError::InsufficientDelegation,
+                StakeError::InsufficientStake,
+            ],
+        ) =>
+        {
+            ErrorCategory::InsufficientStake
+        }
+        _ => ErrorCategory::TransactionFailed,
+    }
+}
+
+fn delegate(
+    global: &GlobalArgs,
+    client: &RpcClient,
+    payer: &Keypair,
+    vote_account_arg: &str,
+    rent: u64,
+) -> Outcome {
+    let Ok(vote_account) = Pubkey::from_str(vote_account_arg) else {
+        return Outcome::failed(ErrorCategory::InvalidPubkey, vote_account_arg);
+    };
+
+    match client.get_account_with_commitment(&vote_account, client.commitment()) {
+        Ok(response) => match response.value {
+            None => return Outcome::failed(ErrorCategory::VoteAccountNotFound, vote_account),
+            Some(account) if account.owner != solana_sdk_ids::vote::ID => {
+                return Outcome::failed(
+                    ErrorCategory::NotVoteAccount,
+                    format!("owned by {}", account.owner),
+                );
+            }
+            Some(_) => {}
+        },
+        Err(err) => return Outcome::failed(ErrorCategory::Rpc, err),
+    }
+
+    let blockhash = match client.get_latest_blockhash() {
+        Ok(blockhash) => blockhash,
+        Err(err) => return Outcome::failed(ErrorCategory::Rpc, err),
+    };
+    let tx = delegate_stake_transaction(
+        payer,
+        &Keypair::new(),
+        &vote_account,
+        rent + STAKE_AMOUNT,
+        blockhash,
+    );
+
+    match helpers::try_execute_transaction(global, client, &tx) {
+        Ok(report) => match report.error {
+            None => Outcome::Delegated {
+                signature: report.signature,
+                simulated: report.simulated,
+            },
+            Some(err) => {
+                let err = TransactionError::from(err);
+                Outcome::failed(categorize(&err), err)
+            }
+        },
+        Err(err) => Outcome::failed(ErrorCategory::Rpc, err),
+    }
+}
+
+pub fn sweep(global: &GlobalArgs, args: &CommandArgs) {
+    let (client, payer) = helpers::client_with_network_override(global, None);
+
+    // Lists saved by `fetch-vote-accounts` are named after the network.
+    let file = args
+        .flag_value("--file")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            ListingFormat::Text.path(VoteStateVersion::V4, &helpers::network_name(&client.url()))
+        });
+    let count = args.parse_flag_value("--count").unwrap_or(10usize);
+    let concurrency = args
+        .parse_flag_value("--concurrency")
+        .unwrap_or(4usize)
+        .max(1);
+
+    let Ok(contents) = std::fs::read_to_string(&file) else {
+        eprintln!("Error: File not found: {}", file.display());
+        eprintln!("Run `make fetch-vote-accounts` first.");
+        std::process::exit(1);
+    };
+    // Listings from `fetch-vote-accounts` put the pubkey in the first column.
+    let mut vote_accounts: Vec<&str> = contents
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .collect();
+    vote_accounts.shuffle(&mut rand::thread_rng());
+    vote_accounts.truncate(count);
+
+    let feature_status = get_feature_status(&client, &FEATURE_ID);
+    let feature_active = feature_status.is_active() || global.force;
+    let rent = stake_account_rent(&client);
+
+    helpers::status!(
+        global,
+        "Delegating {} lamports to {} vote accounts from {} ({} at a time)",
+        STAKE_AMOUNT,
+        vote_accounts.len(),
+        file.display(),
+        concurrency
+    );
+    helpers::status!(global);
+
+    let queue = Mutex::new(vote_accounts.into_iter());
+    let results = Mutex::new(BTreeMap::new());
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency {
+            scope.spawn(|| loop {
+                let Some(vote_account) = queue.lock().unwrap().next() else {
+                    break;
+                };
+                let outcome = if feature_active {
+                    delegate(global, &client, &payer, vote_account, rent)
+                } else {
+                    Outcome::failed(
+                        ErrorCategory::FeatureInactive,
+                        format!("{FEATURE_ID} is {feature_status}"),
+                    )
+                };
+                results
+                    .lock()
+                    .unwrap()
+                    .insert(vote_account.to_string(), outcome);
+            });
+        }
+    });
+    let results = results.into_inner().unwrap();
+
+    if global.output == OutputFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&results).expect("failed to serialize report")
+        );
+        return;
+    }
+
+    let mut failures: BTreeMap<ErrorCategory, usize> = BTreeMap::new();
+    for (vote_account, outcome) in &results {
+        match outcome {
+            Outcome::Delegated { signature, .. } => {
+                println!("{vote_account:<44}  {:<22}  {signature}", "ok");
+            }
+            Outcome::Failed { category, detail } => {
+                *failures.entry(*category).or_default() += 1;
+                println!("{vote_account:<44}  {:<22}  {detail}", category.to_string());
+            }
+        }
+    }
+
+    println!();
+    println!(
+        "Done. Success: {}, Failed: {}",
+        results.len() - failures.values().sum::<usize>(),
+        failures.values().sum::<usize>()
+    );
+    for (category, count) in failures {
+        println!("  {category}: {count}");
+    }
+}
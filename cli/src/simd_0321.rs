@@ -0,0 +1,93 @@
+//! SIMD-0321: Instruction Data Pointer in VM r2.
+
+use {
+    helpers::{CommandArgs, GlobalArgs, Pubkey, Signer, Transaction},
+    simd_0321_interface::{build_instruction, EasterEgg},
+};
+
+/// The feature exercised by this client.
+const FEATURE_ID: Pubkey = agave_feature_set::provide_instruction_data_offset_in_vm_r2::ID;
+
+const PROGRAM: &str = "simd-0321";
+
+pub fn run(global: &GlobalArgs, command: &str, args: &[String]) -> bool {
+    let value_flags: &[&str] = match command {
+        "send" => &["--hex"],
+        _ => &[],
+    };
+    let Some(args) = crate::command_args(args, value_flags, &[]) else {
+        return false;
+    };
+
+    match (command, &args.positional()[..]) {
+        ("send", []) => send(global, &args),
+        _ => return false,
+    }
+    true
+}
+
+fn parse_hex(hex: &str) -> Vec<u8> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+        eprintln!("Invalid --hex: {hex}");
+        std::process::exit(1);
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or_else(|_| {
+                eprintln!("Invalid --hex: {hex}");
+                std::process::exit(1);
+            })
+        })
+        .collect()
+}
+
+/// Send `--hex` bytes as instruction data, or by default raw bytes and an
+/// `EasterEgg` payload.
+fn send(global: &GlobalArgs, args: &CommandArgs) {
+    let (client, payer) = helpers::client_with_network_override(global, None);
+    helpers::feature::require_feature(&client, &FEATURE_ID, global.force);
+
+    let program_id = helpers::program_id(global, &client, PROGRAM);
+
+    let instructions = match args.flag_value("--hex") {
+        Some(hex) => vec![build_instruction(&program_id, parse_hex(hex))],
+        None => {
+            // Instruction 1: random bytes — program will log raw bytes.
+            let random_ix = build_instruction(&program_id, vec![0xDE, 0xAD, 0xBE, 0xEF]);
+
+            // Instruction 2: valid EasterEgg payload — program will print
+            // ASCII owl + message.
+            let egg = EasterEgg::compose("Hoot hoot! You found the secret owl!".into());
+            let egg_ix = build_instruction(&program_id, egg.encode());
+
+            vec![random_ix, egg_ix]
+        }
+    };
+
+    // Build, sign, and send the transaction.
+    let blockhash = client
+        .get_latest_blockhash()
+        .expect("failed to get blockhash");
+    let tx = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer.pubkey()),
+        &[&payer],
+        blockhash,
+    );
+
+    helpers::send_transaction(global, &client, &tx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(parse_hex("deadBEEF"), vec![0xDE, 0xAD, 0xBE, 0xEF]);
+        assert_eq!(parse_hex("0x00ff"), vec![0x00, 0xFF]);
+        assert_eq!(parse_hex(""), Vec::<u8>::new());
+    }
+}
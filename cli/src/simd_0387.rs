@@ -0,0 +1,263 @@
+//! SIMD-0387: BLS Pubkey Management in Vote Accounts.
+
+use {
+    helpers::{
+        keystore::{Keystore, Role},
+        read_keypair_file, GlobalArgs, Keypair, RpcClient, Signer, Transaction,
+    },
+    simd_0387_interface::ProgramInstruction,
+    solana_account::ReadableAccount,
+    solana_pubkey::Pubkey,
+    solana_vote_interface::{
+        instruction::{create_account_with_config, CreateVoteAccountConfig},
+        state::{VoteInit, VoteStateV4, VoteStateVersions},
+    },
+    solana_vote_program::vote_state::create_bls_pubkey_and_proof_of_possession,
+    std::str::FromStr,
+};
+
+/// The feature exercised by this client.
+const FEATURE_ID: Pubkey = agave_feature_set::bls_pubkey_management_in_vote_account::ID;
+
+const PROGRAM: &str = "simd-0387";
+
+pub fn run(global: &GlobalArgs, command: &str, args: &[String]) -> bool {
+    let Some(args) = crate::command_args(args, &[], &[]) else {
+        return false;
+    };
+
+    match (command, &args.positional()[..]) {
+        ("set", []) => set(global, Target::New),
+        ("set", ["latest"]) => set(global, Target::Latest),
+        ("set", [vote_account, keypair]) => set(global, Target::Existing(vote_account, keypair)),
+        ("view", [vote_account]) => view(global, vote_account),
+        _ => return false,
+    }
+    true
+}
+
+/// Which vote account `set` sets a BLS pubkey on.
+enum Target<'a> {
+    /// A freshly created v4 vote account.
+    New,
+    /// A v4 vote account and the path to its authorized voter keypair.
+    Existing(&'a str, &'a str),
+    /// The latest vote account saved with its authorized voter (see
+    /// `helpers::keystore`) on the current network.
+    Latest,
+}
+
+/// Exit unless `authorized_voter` is the authorized voter of the v4 vote
+/// account `vote_pubkey` in the current epoch.
+fn check_authorized_voter(client: &RpcClient, vote_pubkey: &Pubkey, authorized_voter: &Keypair) {
+    let vote_account = client
+        .get_account(vote_pubkey)
+        .expect("failed to fetch vote account");
+    let Ok(VoteStateVersions::V4(vote_state)) = VoteStateVersions::deserialize(vote_account.data())
+    else {
+        eprintln!("Error: {} is not a v4 vote account", vote_pubkey);
+        std::process::exit(1);
+    };
+    // The same lookup the Vote Program does: a voter change scheduled for a
+    // later epoch can't sign yet.
+    let epoch = client
+        .get_epoch_info()
+        .expect("failed to get epoch info")
+        .epoch;
+    let current_voter = vote_state
+        .authorized_voters
+        .get_authorized_voter(epoch)
+        .expect("vote account has no authorized voter");
+    if current_voter != authorized_voter.pubkey() {
+        eprintln!(
+            "Error: {} is not the authorized voter of {} (expected {})",
+            authorized_voter.pubkey(),
+            vote_pubkey,
+            current_voter
+        );
+        std::process::exit(1);
+    }
+}
+
+/// Set a BLS pubkey on `target`, then view it.
+fn set(global: &GlobalArgs, target: Target) {
+    let (client, payer) = helpers::client_with_network_override(global, None);
+    helpers::feature::require_feature(&client, &FEATURE_ID, global.force);
+
+    let program_id = helpers::program_id(global, &client, PROGRAM);
+    let network = helpers::network_name(&client.url());
+    let keystore = Keystore::default();
+    let exit_on_err = |err: String| -> ! {
+        eprintln!("Error: {err}");
+        std::process::exit(1);
+    };
+
+    let mut instructions = Vec::new();
+    let new_vote_account = matches!(target, Target::New).then(Keypair::new);
+
+    let (vote_pubkey, authorized_voter) = match target {
+        Target::Existing(vote_account_arg, keypair_path) => {
+            let vote_pubkey =
+                Pubkey::from_str(vote_account_arg).expect("Invalid vote account pubkey");
+            let authorized_voter =
+                read_keypair_file(keypair_path).expect("failed to read authorized voter keypair");
+            check_authorized_voter(&client, &vote_pubkey, &authorized_voter);
+
+            (vote_pubkey, authorized_voter)
+        }
+        Target::Latest => {
+            let roles = [Role::VoteAccount, Role::AuthorizedVoter];
+            let Some(run) = keystore
+                .latest(&network, &roles)
+                .unwrap_or_else(|err| exit_on_err(err))
+            else {
+                eprintln!("Error: no saved vote account with an authorized voter on {network}");
+                std::process::exit(1);
+            };
+            helpers::status!(global, "Keypairs loaded from:   {}", run.dir().display());
+            let vote_pubkey = run.pubkey(Role::VoteAccount).unwrap_or_else(|| {
+                exit_on_err(format!(
+                    "{} has an invalid vote account",
+                    run.dir().display()
+                ))
+            });
+            let authorized_voter = run
+                .keypair(Role::AuthorizedVoter)
+                .unwrap_or_else(|err| exit_on_err(err));
+            check_authorized_voter(&client, &vote_pubkey, &authorized_voter);
+
+            (vote_pubkey, authorized_voter)
+        }
+        Target::New => {
+            // Generate a fresh v4 vote account. The authorized voter doubles
+            // as the node identity, and the payer can withdraw.
+            let authorized_voter = Keypair::new();
+            let vote_account = new_vote_account.as_ref().unwrap();
+            let vote_pubkey = vote_account.pubkey();
+
+            let space = VoteStateV4::size_of();
+            let lamports = client
+                .get_minimum_balance_for_rent_exemption(space)
+                .expect("failed to get rent exemption");
+
+            instructions.extend(create_account_with_config(
+                &payer.pubkey(),
+                &vote_pubkey,
+                &VoteInit {
+                    node_pubkey: authorized_voter.pubkey(),
+                    authorized_voter: authorized_voter.pubkey(),
+                    authorized_withdrawer: payer.pubkey(),
+                    commission: 0,
+                },
+                lamports,
+                CreateVoteAccountConfig {
+                    space: space as u64,
+                    with_seed: None,
+                },
+            ));
+
+            (vote_pubkey, authorized_voter)
+        }
+    };
+
+    // Keep the current authorized voter, so its keypair can sign for the
+    // account again.
+    let new_authorized_voter = authorized_voter.pubkey();
+    let (bls_pubkey_compressed, bls_proof_of_possession) =
+        create_bls_pubkey_and_proof_of_possession(&vote_pubkey);
+
+    helpers::status!(global, "Payer:                  {}", payer.pubkey());
+    helpers::status!(global, "Vote account:           {}", vote_pubkey);
+    helpers::status!(
+        global,
+        "Authorized voter:       {}",
+        authorized_voter.pubkey()
+    );
+    helpers::status!(
+        global,
+        "BLS pubkey:             {:?}",
+        bls_pubkey_compressed
+    );
+    helpers::status!(global);
+
+    // Set instruction.
+    instructions.push(ProgramInstruction::set(
+        &program_id,
+        &authorized_voter.pubkey(),
+        &vote_pubkey,
+        &new_authorized_voter,
+        &bls_pubkey_compressed,
+        &bls_proof_of_possession,
+    ));
+
+    // View instruction.
+    instructions.push(ProgramInstruction::view(&program_id, &vote_pubkey));
+
+    // Build, sign, and send the transaction.
+    let mut signers: Vec<&Keypair> = vec![&payer, &authorized_voter];
+    signers.extend(new_vote_account.as_ref());
+    let blockhash = client
+        .get_latest_blockhash()
+        .expect("failed to get blockhash");
+    let tx = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer.pubkey()),
+        &signers,
+        blockhash,
+    );
+
+    // Save a new account's keypairs before sending, so they aren't lost if
+    // the account gets created but the client fails waiting for
+    // confirmation.
+    let run = match (&new_vote_account, global.simulate) {
+        (Some(vote_account), false) => {
+            let mut run = keystore
+                .create_run(&network, "0387 set")
+                .unwrap_or_else(|err| exit_on_err(err));
+            for (role, keypair) in [
+                (Role::VoteAccount, vote_account),
+                (Role::AuthorizedVoter, &authorized_voter),
+            ] {
+                run.save(role, keypair)
+                    .unwrap_or_else(|err| exit_on_err(err));
+            }
+            helpers::status!(global, "Keypairs saved to:      {}", run.dir().display());
+            helpers::status!(global);
+            Some(run)
+        }
+        _ => None,
+    };
+
+    let report = helpers::execute_transaction(global, &client, &tx);
+    helpers::status!(global);
+    report.print(global.output);
+    if !report.is_success() {
+        // The transaction was rejected or failed, so no account exists.
+        if let Some(run) = run {
+            run.remove().unwrap_or_else(|err| exit_on_err(err));
+        }
+        std::process::exit(1);
+    }
+}
+
+/// Log the BLS pubkey of an existing v4 vote account.
+fn view(global: &GlobalArgs, vote_account: &str) {
+    let (client, payer) = helpers::client_with_network_override(global, None);
+    helpers::feature::require_feature(&client, &FEATURE_ID, global.force);
+
+    let program_id = helpers::program_id(global, &client, PROGRAM);
+    let vote_pubkey = Pubkey::from_str(vote_account).expect("Invalid vote account pubkey");
+
+    helpers::status!(global, "Vote account:           {}", vote_pubkey);
+    helpers::status!(global);
+
+    let view_ix = ProgramInstruction::view(&program_id, &vote_pubkey);
+
+    let blockhash = client
+        .get_latest_blockhash()
+        .expect("failed to get blockhash");
+    let tx =
+        Transaction::new_signed_with_payer(&[view_ix], Some(&payer.pubkey()), &[&payer], blockhash);
+
+    helpers::send_transaction(global, &client, &tx);
+}
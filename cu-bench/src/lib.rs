@@ -0,0 +1,326 @@
+//! Compute-unit benchmarks for the SIMD programs.
+//!
+//! Each program's `benches/compute_units.rs` (run by `make bench-<prog>`)
+//! measures every instruction variant under each on/off combination of its
+//! feature:
+//!
+//! ```ignore
+//! let mut bench = CuBench::new("simd-0321", &[("r2_data_pointer", feature_id)]);
+//! bench.bench("raw_bytes", || Mollusk::new(&program_id, "simd_0321"), |mollusk| {
+//!     mollusk.process_instruction(&instruction, &[])
+//! });
+//! bench.finish();
+//! ```
+//!
+//! `finish` writes the results to `target/cu-bench/<prog>.md` and `.json` and
+//! compares them to the checked-in `<prog>/benches/baseline.json`, exiting
+//! non-zero if any path uses more than `--threshold <percent>` (default 5)
+//! more compute units than its baseline, or has no baseline at all.
+//! `--save-baseline` overwrites the baseline with the current results
+//! instead.
+
+use {
+    feature_matrix::{describe, FeatureMatrix},
+    mollusk_svm::{result::InstructionResult, Mollusk},
+    serde::{Deserialize, Serialize},
+    solana_pubkey::Pubkey,
+    std::{
+        fmt::Write,
+        fs, io,
+        path::{Path, PathBuf},
+    },
+};
+
+const DEFAULT_THRESHOLD_PERCENT: f64 = 5.0;
+
+/// The compute units one instruction variant consumed under one feature
+/// combination.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Measurement {
+    pub instruction: String,
+    /// e.g. `vote_state_v4=on`.
+    pub features: String,
+    pub compute_units: u64,
+    /// `success`, or the error the instruction failed with.
+    pub result: String,
+}
+
+/// A measurement alongside its baseline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    pub measurement: Measurement,
+    pub baseline: Option<u64>,
+    pub regressed: bool,
+}
+
+/// Compare each measurement to the baseline entry for the same instruction
+/// and features. A measurement regresses if it exceeds its baseline by more
+/// than `threshold_percent`.
+pub fn compare(
+    measurements: &[Measurement],
+    baseline: &[Measurement],
+    threshold_percent: f64,
+) -> Vec<Comparison> {
+    measurements
+        .iter()
+        .map(|measurement| {
+            let baseline = baseline
+                .iter()
+                .find(|entry| {
+                    entry.instruction == measurement.instruction
+                        && entry.features == measurement.features
+                })
+                .map(|entry| entry.compute_units);
+            let regressed = baseline.is_some_and(|baseline| {
+                measurement.compute_units as f64
+                    > baseline as f64 * (1.0 + threshold_percent / 100.0)
+            });
+            Comparison {
+                measurement: measurement.clone(),
+                baseline,
+                regressed,
+            }
+        })
+        .collect()
+}
+
+/// Describe every path in `comparisons` that regressed or has no baseline
+/// entry, or return `Ok` if there are none.
+pub fn check(comparisons: &[Comparison], threshold_percent: f64) -> Result<(), String> {
+    let count = |matches: fn(&Comparison) -> bool| {
+        comparisons
+            .iter()
+            .filter(|comparison| matches(comparison))
+            .count()
+    };
+    let missing = count(|comparison| comparison.baseline.is_none());
+    let regressed = count(|comparison| comparison.regressed);
+
+    let mut failures = Vec::new();
+    if missing > 0 {
+        failures.push(format!("{missing} path(s) have no baseline"));
+    }
+    if regressed > 0 {
+        failures.push(format!(
+            "{regressed} path(s) regressed by more than {threshold_percent}%"
+        ));
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures.join(", "))
+    }
+}
+
+/// A markdown table of `comparisons`.
+pub fn render_markdown(program: &str, comparisons: &[Comparison]) -> String {
+    let mut table = format!("## {program}\n\n");
+    table.push_str("| instruction | features | CUs | baseline | delta | result |\n");
+    table.push_str("|---|---|---|---|---|---|\n");
+    for comparison in comparisons {
+        let measurement = &comparison.measurement;
+        let (baseline, delta) = match comparison.baseline {
+            Some(baseline) => {
+                let delta = measurement.compute_units as i64 - baseline as i64;
+                let percent = if baseline == 0 {
+                    0.0
+                } else {
+                    delta as f64 * 100.0 / baseline as f64
+                };
+                let flag = if comparison.regressed {
+                    " regressed"
+                } else {
+                    ""
+                };
+                (
+                    baseline.to_string(),
+                    format!("{delta:+} ({percent:+.1}%){flag}"),
+                )
+            }
+            None => ("-".to_string(), "-".to_string()),
+        };
+        writeln!(
+            table,
+            "| {} | {} | {} | {} | {} | {} |",
+            measurement.instruction,
+            measurement.features,
+            measurement.compute_units,
+            baseline,
+            delta,
+            measurement.result
+        )
+        .unwrap();
+    }
+    table
+}
+
+/// Measures a program's instruction variants. See the crate docs.
+pub struct CuBench {
+    program: &'static str,
+    matrix: FeatureMatrix,
+    measurements: Vec<Measurement>,
+}
+
+impl CuBench {
+    /// Benchmark `program` (e.g. `simd-0185`) under every on/off combination
+    /// of `features`.
+    pub fn new(program: &'static str, features: &[(&'static str, Pubkey)]) -> Self {
+        Self {
+            program,
+            matrix: FeatureMatrix::new(features),
+            measurements: Vec::new(),
+        }
+    }
+
+    /// Measure `run` under each feature combination, with a fresh `Mollusk`
+    /// from `mollusk` each time.
+    pub fn bench(
+        &mut self,
+        instruction: &str,
+        mollusk: impl Fn() -> Mollusk,
+        run: impl Fn(&Mollusk) -> InstructionResult,
+    ) -> &mut Self {
+        for combination in self.matrix.combinations() {
+            let mut mollusk = mollusk();
+            combination.apply(&mut mollusk);
+            let result = run(&mollusk);
+            self.measurements.push(Measurement {
+                instruction: instruction.to_string(),
+                features: combination.to_string(),
+                compute_units: result.compute_units_consumed,
+                result: describe(&result.program_result),
+            });
+        }
+        self
+    }
+
+    /// Write the results, then save them as the baseline (`--save-baseline`)
+    /// or check them against it.
+    pub fn finish(self) {
+        let args = std::env::args().collect::<Vec<_>>();
+        let threshold_percent = match args.iter().position(|arg| arg == "--threshold") {
+            Some(i) => args
+                .get(i + 1)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(|| panic!("--threshold takes a percentage")),
+            None => DEFAULT_THRESHOLD_PERCENT,
+        };
+
+        let baseline_path = baseline_path(self.program);
+        if args.iter().any(|arg| arg == "--save-baseline") {
+            write_json(&baseline_path, &self.measurements);
+            println!("Saved baseline to {}", baseline_path.display());
+            return;
+        }
+
+        // A missing baseline fails the run below, once the results are out.
+        let baseline = match fs::read_to_string(&baseline_path) {
+            Ok(contents) => serde_json::from_str::<Vec<Measurement>>(&contents)
+                .unwrap_or_else(|err| panic!("invalid {}: {err}", baseline_path.display())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => panic!("failed to read {}: {err}", baseline_path.display()),
+        };
+
+        let comparisons = compare(&self.measurements, &baseline, threshold_percent);
+        let markdown = render_markdown(self.program, &comparisons);
+        print!("{markdown}");
+
+        let out_dir = workspace_root().join("target/cu-bench");
+        fs::create_dir_all(&out_dir).expect("failed to create target/cu-bench");
+        fs::write(out_dir.join(format!("{}.md", self.program)), &markdown)
+            .expect("failed to write markdown results");
+        write_json(
+            &out_dir.join(format!("{}.json", self.program)),
+            &self.measurements,
+        );
+
+        if let Err(err) = check(&comparisons, threshold_percent) {
+            eprintln!(
+                "{err} in {}; run with --save-baseline if this is expected",
+                baseline_path.display()
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("cu-bench lives in the workspace")
+        .to_path_buf()
+}
+
+fn baseline_path(program: &str) -> PathBuf {
+    workspace_root().join(program).join("benches/baseline.json")
+}
+
+fn write_json(path: &Path, measurements: &[Measurement]) {
+    let json = serde_json::to_string_pretty(measurements).expect("failed to serialize results");
+    fs::write(path, json + "\n")
+        .unwrap_or_else(|err| panic!("failed to write {}: {err}", path.display()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(instruction: &str, features: &str, compute_units: u64) -> Measurement {
+        Measurement {
+            instruction: instruction.to_string(),
+            features: features.to_string(),
+            compute_units,
+            result: "success".to_string(),
+        }
+    }
+
+    #[test]
+    fn compare_against_baseline() {
+        let baseline = [
+            measurement("create", "vote_state_v4=on", 1_000),
+            measurement("create", "vote_state_v4=off", 1_000),
+        ];
+        let measurements = [
+            measurement("create", "vote_state_v4=on", 1_050),
+            measurement("create", "vote_state_v4=off", 1_051),
+            measurement("view", "vote_state_v4=on", 500),
+        ];
+
+        let comparisons = compare(&measurements, &baseline, 5.0);
+        assert_eq!(
+            comparisons
+                .iter()
+                .map(|comparison| (comparison.baseline, comparison.regressed))
+                .collect::<Vec<_>>(),
+            [(Some(1_000), false), (Some(1_000), true), (None, false)]
+        );
+
+        let markdown = render_markdown("simd-0185", &comparisons);
+        assert!(markdown
+            .contains("| create | vote_state_v4=on | 1050 | 1000 | +50 (+5.0%) | success |"));
+        assert!(markdown
+            .contains("| create | vote_state_v4=off | 1051 | 1000 | +51 (+5.1%) regressed |"));
+        assert!(markdown.contains("| view | vote_state_v4=on | 500 | - | - | success |"));
+
+        assert_eq!(
+            check(&comparisons, 5.0),
+            Err("1 path(s) have no baseline, 1 path(s) regressed by more than 5%".to_string())
+        );
+        assert_eq!(check(&comparisons[..1], 5.0), Ok(()));
+        // Without a baseline file, every path is missing.
+        assert_eq!(
+            check(&compare(&measurements[..1], &[], 5.0), 5.0),
+            Err("1 path(s) have no baseline".to_string())
+        );
+    }
+
+    #[test]
+    fn measurement_json_roundtrip() {
+        let measurements = vec![measurement("set", "bls_pubkey_management=off", 42)];
+        let json = serde_json::to_string(&measurements).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<Measurement>>(&json).unwrap(),
+            measurements
+        );
+    }
+}
@@ -0,0 +1,310 @@
+//! Run a mollusk test under every on/off combination of a set of features.
+//!
+//! Each program's `fail_feature_disabled` test turns off the one feature it
+//! exercises. Validators see every mix of SIMDs during staged rollouts, so
+//! `FeatureMatrix` runs the same instructions against all 2^n combinations
+//! and compares each outcome to an expectation:
+//!
+//! ```ignore
+//! FeatureMatrix::new(&[
+//!     ("vote_state_v4", vote_state_v4::id()),
+//!     ("bls_pubkey_management", bls_pubkey_management_in_vote_account::id()),
+//! ])
+//! .run(
+//!     || Mollusk::new(&program_id, "simd_0387"),
+//!     |mollusk, _| mollusk.process_instruction_chain(&instructions, &accounts),
+//!     |combination| match combination.all_active() {
+//!         true => ProgramResult::Success,
+//!         false => ProgramResult::Failure(ProgramError::InvalidInstructionData),
+//!     },
+//! )
+//! .assert_passed();
+//! ```
+
+use {
+    mollusk_svm::{
+        result::{InstructionResult, ProgramResult},
+        Mollusk,
+    },
+    program_error::TestProgramError,
+    solana_program_error::ProgramError,
+    solana_pubkey::Pubkey,
+    std::fmt,
+};
+
+/// More features than this would make for an unreadably long report.
+const MAX_FEATURES: usize = 8;
+
+/// The features to toggle, by display name and ID.
+#[derive(Clone, Debug)]
+pub struct FeatureMatrix {
+    features: Vec<(&'static str, Pubkey)>,
+}
+
+impl FeatureMatrix {
+    pub fn new(features: &[(&'static str, Pubkey)]) -> Self {
+        assert!(
+            !features.is_empty() && features.len() <= MAX_FEATURES,
+            "a feature matrix takes 1 to {MAX_FEATURES} features"
+        );
+        Self {
+            features: features.to_vec(),
+        }
+    }
+
+    /// Every on/off combination, starting with all features on.
+    pub fn combinations(&self) -> Vec<Combination> {
+        let n = self.features.len();
+        (0..1u32 << n)
+            .map(|mask| Combination {
+                features: self
+                    .features
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (name, id))| (*name, *id, mask & (1 << (n - 1 - i)) == 0))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// For each combination, build a `Mollusk` with `mollusk`, set the
+    /// features, run `test`, and compare its result to `expected`.
+    pub fn run(
+        &self,
+        mollusk: impl Fn() -> Mollusk,
+        mut test: impl FnMut(&Mollusk, &Combination) -> InstructionResult,
+        expected: impl Fn(&Combination) -> ProgramResult,
+    ) -> MatrixReport {
+        let rows = self
+            .combinations()
+            .into_iter()
+            .map(|combination| {
+                let mut mollusk = mollusk();
+                combination.apply(&mut mollusk);
+                let result = test(&mollusk, &combination);
+                MatrixRow {
+                    expected: expected(&combination),
+                    actual: result.program_result,
+                    compute_units_consumed: result.compute_units_consumed,
+                    combination,
+                }
+            })
+            .collect();
+        MatrixReport {
+            names: self.features.iter().map(|(name, _)| *name).collect(),
+            rows,
+        }
+    }
+}
+
+/// One on/off assignment of a matrix's features.
+#[derive(Clone, Debug)]
+pub struct Combination {
+    features: Vec<(&'static str, Pubkey, bool)>,
+}
+
+impl Combination {
+    pub fn is_active(&self, feature_id: &Pubkey) -> bool {
+        self.features
+            .iter()
+            .find(|(_, id, _)| id == feature_id)
+            .unwrap_or_else(|| panic!("{feature_id} is not in the matrix"))
+            .2
+    }
+
+    pub fn all_active(&self) -> bool {
+        self.features.iter().all(|(_, _, active)| *active)
+    }
+
+    /// Activate or deactivate each feature in `mollusk`'s feature set.
+    pub fn apply(&self, mollusk: &mut Mollusk) {
+        for (_, id, active) in &self.features {
+            if *active {
+                mollusk.feature_set.activate(id, 0);
+            } else {
+                mollusk.feature_set.deactivate(id);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Combination {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let features = self
+            .features
+            .iter()
+            .map(|(name, _, active)| format!("{name}={}", if *active { "on" } else { "off" }))
+            .collect::<Vec<_>>();
+        write!(f, "{}", features.join(", "))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MatrixRow {
+    pub combination: Combination,
+    pub expected: ProgramResult,
+    pub actual: ProgramResult,
+    pub compute_units_consumed: u64,
+}
+
+impl MatrixRow {
+    pub fn passed(&self) -> bool {
+        self.expected == self.actual
+    }
+}
+
+/// The outcome of every combination, printed as a markdown table.
+#[derive(Clone, Debug)]
+pub struct MatrixReport {
+    names: Vec<&'static str>,
+    pub rows: Vec<MatrixRow>,
+}
+
+impl MatrixReport {
+    pub fn passed(&self) -> bool {
+        self.rows.iter().all(MatrixRow::passed)
+    }
+
+    /// Print the table, and panic listing the failed combinations if any
+    /// outcome differs from its expectation.
+    pub fn assert_passed(&self) {
+        println!("{self}");
+        let failed = self
+            .rows
+            .iter()
+            .filter(|row| !row.passed())
+            .map(|row| {
+                format!(
+                    "  {}: expected {}, got {}",
+                    row.combination,
+                    describe(&row.expected),
+                    describe(&row.actual)
+                )
+            })
+            .collect::<Vec<_>>();
+        assert!(
+            failed.is_empty(),
+            "unexpected outcomes:\n{}",
+            failed.join("\n")
+        );
+    }
+}
+
+impl fmt::Display for MatrixReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for name in &self.names {
+            write!(f, "| {name} ")?;
+        }
+        writeln!(f, "| expected | actual | CUs | |")?;
+        writeln!(f, "{}|---|---|---|---|", "|---".repeat(self.names.len()))?;
+        for row in &self.rows {
+            for (_, _, active) in &row.combination.features {
+                write!(f, "| {} ", if *active { "on" } else { "off" })?;
+            }
+            writeln!(
+                f,
+                "| {} | {} | {} | {} |",
+                describe(&row.expected),
+                describe(&row.actual),
+                row.compute_units_consumed,
+                if row.passed() { "ok" } else { "FAIL" }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A program result, with custom codes named after `TestProgramError`.
+pub fn describe(result: &ProgramResult) -> String {
+    match result {
+        ProgramResult::Success => "success".to_string(),
+        ProgramResult::Failure(ProgramError::Custom(code)) => TestProgramError::try_from(*code)
+            .map(|err| format!("{err:?}"))
+            .unwrap_or_else(|code| format!("Custom({code})")),
+        ProgramResult::Failure(err) => format!("{err:?}"),
+        ProgramResult::UnknownError(err) => format!("{err:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        agave_feature_set::{bls_pubkey_management_in_vote_account, vote_state_v4},
+    };
+
+    fn matrix() -> FeatureMatrix {
+        FeatureMatrix::new(&[
+            ("vote_state_v4", vote_state_v4::id()),
+            (
+                "bls_pubkey_management",
+                bls_pubkey_management_in_vote_account::id(),
+            ),
+        ])
+    }
+
+    /// Succeeds only with both features on, without running a program.
+    fn fake_test(mollusk: &Mollusk, _: &Combination) -> InstructionResult {
+        let feature_set = &mollusk.feature_set;
+        let program_result = if feature_set.is_active(&vote_state_v4::id())
+            && feature_set.is_active(&bls_pubkey_management_in_vote_account::id())
+        {
+            ProgramResult::Success
+        } else {
+            ProgramResult::Failure(TestProgramError::NotV4VoteState.into())
+        };
+        InstructionResult {
+            program_result,
+            ..InstructionResult::default()
+        }
+    }
+
+    #[test]
+    fn combinations() {
+        let combinations = matrix()
+            .combinations()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            combinations,
+            [
+                "vote_state_v4=on, bls_pubkey_management=on",
+                "vote_state_v4=on, bls_pubkey_management=off",
+                "vote_state_v4=off, bls_pubkey_management=on",
+                "vote_state_v4=off, bls_pubkey_management=off",
+            ]
+        );
+    }
+
+    #[test]
+    fn run_sets_features() {
+        let report = matrix().run(Mollusk::default, fake_test, |combination| {
+            if combination.all_active() {
+                ProgramResult::Success
+            } else {
+                ProgramResult::Failure(TestProgramError::NotV4VoteState.into())
+            }
+        });
+        assert!(report.passed());
+        report.assert_passed();
+
+        let table = report.to_string();
+        assert!(table.starts_with("| vote_state_v4 | bls_pubkey_management | expected |"));
+        assert!(table.contains("| off | off | NotV4VoteState | NotV4VoteState | 0 | ok |"));
+    }
+
+    #[test]
+    #[should_panic(expected = "vote_state_v4=on, bls_pubkey_management=off: expected success")]
+    fn report_mismatch() {
+        matrix()
+            .run(Mollusk::default, fake_test, |combination| {
+                if combination.is_active(&vote_state_v4::id()) {
+                    ProgramResult::Success
+                } else {
+                    ProgramResult::Failure(TestProgramError::NotV4VoteState.into())
+                }
+            })
+            .assert_passed();
+    }
+}
@@ -0,0 +1,288 @@
+//! Deploy `simd-*` programs and record them in `deployments.toml`, or with
+//! `--verify`, compare the ELF each cluster is running against the local
+//! `target/deploy` build.
+
+use {
+    helpers::{
+        deployments::{
+            elf_sha256, local_elf_path, resolve_program_id, workspace_programs, Deployments,
+            OnChainProgram,
+        },
+        read_keypair_file, CommandArgs, GlobalArgs, Pubkey, RpcClient, Signer,
+    },
+    std::{fmt, fs, path::PathBuf, process::Command},
+};
+
+fn usage(program_name: &str) -> ! {
+    eprintln!(
+        "Usage: {} [programs...] [--verify] [--program-keypair <path>] [options]",
+        program_name
+    );
+    eprintln!("  programs: e.g. simd-0185 (default: every simd-* program)");
+    eprintln!("  --verify: compare on-chain program data against target/deploy");
+    eprintln!("  --program-id: address of a single program to deploy or verify");
+    eprintln!("  --program-keypair: keypair for a first deployment of a single program");
+    eprintln!("                     (default: <prog>/keypair.json)");
+    eprintln!("  --url <network|url>, --keypair <path>: as for the simd client");
+    std::process::exit(1);
+}
+
+fn exit_with(err: String) -> ! {
+    eprintln!("Error: {err}");
+    std::process::exit(1);
+}
+
+fn main() {
+    let global = GlobalArgs::parse();
+    let args = CommandArgs::parse(&global.rest, &["--program-keypair"], &["--verify"])
+        .unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            usage(&global.program_name)
+        });
+    let known = workspace_programs();
+    let programs = match args.positional()[..] {
+        [] => known,
+        ref programs => {
+            if let Some(unknown) = programs
+                .iter()
+                .find(|program| !known.iter().any(|known| known == *program))
+            {
+                eprintln!("Unknown program: {unknown}");
+                usage(&global.program_name);
+            }
+            programs.iter().map(|program| program.to_string()).collect()
+        }
+    };
+
+    // Both name a single program's address, for deploying and verifying.
+    for (flag, passed) in [
+        ("--program-id", global.program_id.is_some()),
+        (
+            "--program-keypair",
+            args.flag_value("--program-keypair").is_some(),
+        ),
+    ] {
+        if passed && programs.len() != 1 {
+            eprintln!("{flag} needs exactly one program");
+            usage(&global.program_name);
+        }
+    }
+
+    let client = helpers::rpc_client_with_network_override(&global, None);
+    let network = helpers::network_name(&client.url());
+    helpers::status!(global, "Network: {}", network);
+    helpers::status!(global);
+
+    if args.has_flag("--verify") {
+        verify(&global, &client, &network, &programs);
+    } else {
+        deploy(&global, &args, &client, &network, &programs);
+    }
+}
+
+/// Deploy each program with `solana program deploy` and record the result.
+fn deploy(
+    global: &GlobalArgs,
+    args: &CommandArgs,
+    client: &RpcClient,
+    network: &str,
+    programs: &[String],
+) {
+    let mut deployments = Deployments::load().unwrap_or_else(|err| exit_with(err));
+    let payer = global
+        .keypair
+        .clone()
+        .unwrap_or_else(|| helpers::load_config().keypair_path);
+
+    for program in programs {
+        let elf_path = local_elf_path(program);
+        let elf = fs::read(&elf_path).unwrap_or_else(|err| {
+            exit_with(format!(
+                "failed to read {} ({err}); run `make build-{program}` first",
+                elf_path.display()
+            ))
+        });
+
+        let (program_id, source) = resolve_program_id(program, network, global.program_id)
+            .unwrap_or_else(|err| exit_with(err));
+        let program_id_arg = program_id_arg(args, client, program, &program_id);
+        helpers::status!(
+            global,
+            "Deploying {} to {} (from {})",
+            program,
+            program_id,
+            source
+        );
+
+        let status = Command::new("solana")
+            .args(["program", "deploy"])
+            .arg(&elf_path)
+            .args(["--program-id", &program_id_arg])
+            .args(["--url", &client.url()])
+            .args(["--keypair", &payer])
+            .status()
+            .expect("failed to run `solana program deploy`");
+        if !status.success() {
+            exit_with(format!("failed to deploy {program}"));
+        }
+
+        let on_chain = match OnChainProgram::fetch(client, &program_id) {
+            Ok(Some(on_chain)) => on_chain,
+            Ok(None) => exit_with(format!("{program_id} not found after deploying")),
+            Err(err) => exit_with(err),
+        };
+        let deployment = on_chain.deployment(&program_id);
+        if deployment.elf_sha256 != Some(elf_sha256(&elf)) {
+            helpers::status!(
+                global,
+                "Warning: on-chain program data differs from {}",
+                elf_path.display()
+            );
+        }
+
+        helpers::status!(global, "Slot:               {}", on_chain.slot);
+        helpers::status!(
+            global,
+            "Upgrade authority:  {}",
+            deployment.upgrade_authority.as_deref().unwrap_or("none")
+        );
+        helpers::status!(
+            global,
+            "ELF SHA-256:        {}",
+            deployment.elf_sha256.as_deref().unwrap_or_default()
+        );
+        helpers::status!(global);
+
+        // Save after each program so earlier deployments are kept if a later
+        // one fails.
+        deployments.insert(program, network, deployment);
+        deployments.save().unwrap_or_else(|err| exit_with(err));
+    }
+
+    helpers::status!(global, "Recorded in {}", Deployments::path().display());
+}
+
+/// What to pass `solana program deploy` as `--program-id`: the address to
+/// upgrade a program that exists, or else the keypair to create it with,
+/// from `--program-keypair` or `<prog>/keypair.json`.
+fn program_id_arg(
+    args: &CommandArgs,
+    client: &RpcClient,
+    program: &str,
+    program_id: &Pubkey,
+) -> String {
+    let exists = client
+        .get_account_with_commitment(program_id, client.commitment())
+        .unwrap_or_else(|err| exit_with(format!("failed to fetch {program_id}: {err}")))
+        .value
+        .is_some();
+    if exists {
+        return program_id.to_string();
+    }
+
+    let keypair_path = args
+        .flag_value("--program-keypair")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| helpers::workspace_root().join(program).join("keypair.json"));
+    let keypair = read_keypair_file(&keypair_path).unwrap_or_else(|err| {
+        exit_with(format!(
+            "{program_id} isn't deployed yet, so creating it needs its keypair: failed to read {} \
+             ({err})",
+            keypair_path.display()
+        ))
+    });
+    if keypair.pubkey() != *program_id {
+        exit_with(format!(
+            "{} is the keypair for {}, not {program_id}",
+            keypair_path.display(),
+            keypair.pubkey()
+        ));
+    }
+    keypair_path.display().to_string()
+}
+
+/// How a cluster's copy of a program compares to the local build.
+enum Verification {
+    Match,
+    Mismatch,
+    NoLocalBuild,
+    NotDeployed,
+    Error(String),
+}
+
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Match => write!(f, "match"),
+            Self::Mismatch => write!(f, "MISMATCH"),
+            Self::NoLocalBuild => write!(f, "no local build"),
+            Self::NotDeployed => write!(f, "not deployed"),
+            Self::Error(err) => write!(f, "error: {err}"),
+        }
+    }
+}
+
+/// Print each program's on-chain and local hashes, exiting non-zero unless
+/// every program matches.
+fn verify(global: &GlobalArgs, client: &RpcClient, network: &str, programs: &[String]) {
+    let deployments = Deployments::load().unwrap_or_else(|err| exit_with(err));
+    let short = |hash: &Option<String>| hash.as_deref().map_or("-".to_string(), |h| h[..12].into());
+
+    println!(
+        "{:<10}  {:<44}  {:>10}  {:<12}  {:<12}  status",
+        "program", "program id", "slot", "on-chain", "local"
+    );
+
+    let mut all_match = true;
+    for program in programs {
+        let local = fs::read(local_elf_path(program))
+            .ok()
+            .map(|elf| elf_sha256(&elf));
+
+        let (program_id, on_chain) = match resolve_program_id(program, network, global.program_id) {
+            Ok((program_id, _)) => (
+                program_id.to_string(),
+                OnChainProgram::fetch(client, &program_id),
+            ),
+            Err(err) => ("-".to_string(), Err(err)),
+        };
+        let (slot, on_chain_hash, verification) = match on_chain {
+            Ok(Some(on_chain)) => {
+                let hash = elf_sha256(&on_chain.elf);
+                let verification = match &local {
+                    None => Verification::NoLocalBuild,
+                    Some(local) if *local == hash => Verification::Match,
+                    Some(_) => Verification::Mismatch,
+                };
+                (on_chain.slot.to_string(), Some(hash), verification)
+            }
+            Ok(None) => ("-".to_string(), None, Verification::NotDeployed),
+            Err(err) => ("-".to_string(), None, Verification::Error(err)),
+        };
+
+        let stale_manifest = deployments
+            .get(program, network)
+            .and_then(|deployment| deployment.elf_sha256.as_ref())
+            .is_some_and(|recorded| Some(recorded) != on_chain_hash.as_ref());
+
+        println!(
+            "{:<10}  {:<44}  {:>10}  {:<12}  {:<12}  {}{}",
+            program,
+            program_id,
+            slot,
+            short(&on_chain_hash),
+            short(&local),
+            verification,
+            if stale_manifest {
+                " (deployments.toml out of date)"
+            } else {
+                ""
+            },
+        );
+        all_match &= matches!(verification, Verification::Match);
+    }
+
+    if !all_match {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,66 @@
+use {
+    helpers::{
+        feature::{feature_name, get_feature_statuses, SIMD_FEATURES},
+        CommandArgs, GlobalArgs,
+    },
+    solana_pubkey::Pubkey,
+};
+
+fn usage(program_name: &str) -> ! {
+    eprintln!("Usage: {} [network] [--all]", program_name);
+    eprintln!("  network: localnet, devnet, testnet, or mainnet (optional)");
+    eprintln!("  --all: list every known feature, not just the SIMDs under test");
+    std::process::exit(1);
+}
+
+fn main() {
+    let global = GlobalArgs::parse();
+    let args = CommandArgs::parse(&global.rest, &[], &["--all"]).unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        usage(&global.program_name)
+    });
+
+    // program_name [network] [--all]
+    let list_all = args.has_flag("--all");
+    let network_override = match args.positional()[..] {
+        [] => None,
+        [network] => Some(network.to_string()),
+        _ => usage(&global.program_name),
+    };
+
+    let client = helpers::rpc_client_with_network_override(&global, network_override);
+    println!();
+
+    if list_all {
+        let mut features = agave_feature_set::FEATURE_NAMES
+            .iter()
+            .map(|(id, name)| (*id, *name))
+            .collect::<Vec<_>>();
+        features.sort_by_key(|(_, name)| *name);
+
+        let ids = features.iter().map(|(id, _)| *id).collect::<Vec<Pubkey>>();
+        let statuses = get_feature_statuses(&client, &ids);
+
+        for ((id, name), status) in features.iter().zip(statuses) {
+            println!("{:<44}  {:<40}  {}", id, status.to_string(), name);
+        }
+        return;
+    }
+
+    let ids = SIMD_FEATURES
+        .iter()
+        .map(|(_, id)| *id)
+        .collect::<Vec<Pubkey>>();
+    let statuses = get_feature_statuses(&client, &ids);
+
+    for ((simd, id), status) in SIMD_FEATURES.iter().zip(statuses) {
+        println!(
+            "{}: {}",
+            simd,
+            feature_name(id).unwrap_or("unknown feature")
+        );
+        println!("  Feature ID:  {}", id);
+        println!("  Status:      {}", status);
+        println!();
+    }
+}
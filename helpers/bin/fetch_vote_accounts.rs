@@ -0,0 +1,80 @@
+use helpers::{
+    vote::{fetch_vote_accounts, ListingFormat, VoteAccountFilter, VoteStateVersion},
+    CommandArgs, GlobalArgs,
+};
+
+fn usage(program_name: &str) -> ! {
+    eprintln!(
+        "Usage: {} [network] [--state-version <v1_14_11|v3|v4>] [--bls <yes|no>] \
+         [--min-commission-bps <n>] [--max-commission-bps <n>] [--format <text|csv|json>]",
+        program_name
+    );
+    eprintln!("  network: localnet, devnet, testnet, or mainnet (optional)");
+    eprintln!("  --state-version: vote state version to match (default: v4)");
+    eprintln!("  --bls: only v4 accounts with (yes) or without (no) a BLS public key");
+    eprintln!("  --min/max-commission-bps: inflation rewards commission range, in bps");
+    eprintln!("  --format: output file format (default: text)");
+    std::process::exit(1);
+}
+
+fn main() {
+    let global = GlobalArgs::parse();
+    let args = CommandArgs::parse(
+        &global.rest,
+        &[
+            "--state-version",
+            "--bls",
+            "--min-commission-bps",
+            "--max-commission-bps",
+            "--format",
+        ],
+        &[],
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        usage(&global.program_name)
+    });
+
+    // program_name [network]
+    let network_override = match args.positional()[..] {
+        [] => None,
+        [network] => Some(network.to_string()),
+        _ => usage(&global.program_name),
+    };
+
+    let filter = VoteAccountFilter {
+        version: args
+            .parse_flag_value::<VoteStateVersion>("--state-version")
+            .unwrap_or_default(),
+        has_bls_key: args.flag_value("--bls").map(|value| match value {
+            "yes" => true,
+            "no" => false,
+            _ => usage(&global.program_name),
+        }),
+        min_commission_bps: args.parse_flag_value("--min-commission-bps"),
+        max_commission_bps: args.parse_flag_value("--max-commission-bps"),
+        ..VoteAccountFilter::default()
+    };
+    let format = args
+        .parse_flag_value::<ListingFormat>("--format")
+        .unwrap_or_default();
+
+    let client = helpers::rpc_client_with_network_override(&global, network_override);
+    let outfile = format.path(filter.version, &helpers::network_name(&client.url()));
+    println!("Fetching {} vote accounts...", filter.version);
+
+    let summaries = fetch_vote_accounts(&client, &filter).unwrap_or_else(|err| {
+        eprintln!("Error: {err}");
+        std::process::exit(1);
+    });
+    format
+        .write(&outfile, &summaries)
+        .expect("failed to write vote accounts");
+
+    println!(
+        "Saved {} {} vote accounts to {}",
+        summaries.len(),
+        filter.version,
+        outfile.display()
+    );
+}
@@ -0,0 +1,219 @@
+//! Command-line parsing shared by every client binary.
+//!
+//! The command line is parsed once, into `GlobalArgs`, which is passed to the
+//! helpers that need a global option (RPC client, payer, report output).
+//! Global options can appear anywhere. The remaining arguments are parsed by
+//! the command into `CommandArgs`, against the options it declares, so a
+//! mistyped flag is an error rather than ignored.
+
+use {
+    crate::OutputFormat, solana_commitment_config::CommitmentConfig, solana_pubkey::Pubkey,
+    std::str::FromStr,
+};
+
+/// Usage lines for the options in `GlobalArgs`.
+pub const GLOBAL_OPTIONS_USAGE: &str = "\
+Global options:
+  --url <network|url>     localnet, devnet, testnet, mainnet, or an RPC URL
+                          (default: solana config)
+  --keypair <path>        payer keypair (default: solana config)
+  --program-id <pubkey>   program to invoke (default: $SIMD_XXXX_PROGRAM_ID,
+                          deployments.toml, then <prog>/keypair.json)
+  --commitment <level>    processed, confirmed, or finalized (default: confirmed)
+  --output <text|json>    report format (default: text)
+  --simulate              simulate instead of sending
+  --force                 send even if the feature is not active";
+
+/// Parse `value`, the value given for `flag`, exiting with an error if it is
+/// invalid.
+fn parse_value<T: FromStr>(flag: &str, value: Option<&str>) -> Option<T> {
+    let value = value?;
+    match value.parse() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            eprintln!("Invalid value for {flag}: {value}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Options accepted by every client command, and the arguments left for the
+/// command itself.
+#[derive(Clone, Debug)]
+pub struct GlobalArgs {
+    /// The program name, as invoked.
+    pub program_name: String,
+    /// A network moniker or RPC URL.
+    pub url: Option<String>,
+    /// Path to the payer keypair.
+    pub keypair: Option<String>,
+    pub program_id: Option<Pubkey>,
+    pub commitment: CommitmentConfig,
+    pub output: OutputFormat,
+    pub simulate: bool,
+    pub force: bool,
+    /// Every other argument, in order: the command and its own arguments.
+    pub rest: Vec<String>,
+}
+
+impl GlobalArgs {
+    /// Parse the process's command line.
+    pub fn parse() -> Self {
+        Self::parse_from(std::env::args())
+    }
+
+    /// Parse `args`, starting with the program name.
+    pub fn parse_from(args: impl IntoIterator<Item = String>) -> Self {
+        let mut args = args.into_iter();
+        let mut global = Self {
+            program_name: args.next().unwrap_or_default(),
+            url: None,
+            keypair: None,
+            program_id: None,
+            commitment: CommitmentConfig::confirmed(),
+            output: OutputFormat::default(),
+            simulate: false,
+            force: false,
+            rest: Vec::new(),
+        };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--url" => global.url = args.next(),
+                "--keypair" => global.keypair = args.next(),
+                "--program-id" => global.program_id = parse_value(&arg, args.next().as_deref()),
+                "--commitment" => {
+                    if let Some(commitment) = parse_value(&arg, args.next().as_deref()) {
+                        global.commitment = commitment;
+                    }
+                }
+                "--output" => {
+                    if let Some(output) = parse_value(&arg, args.next().as_deref()) {
+                        global.output = output;
+                    }
+                }
+                "--simulate" => global.simulate = true,
+                "--force" => global.force = true,
+                _ => global.rest.push(arg),
+            }
+        }
+        global
+    }
+}
+
+/// A command's own arguments: its positional arguments and the options it
+/// accepts besides the global ones.
+#[derive(Clone, Debug, Default)]
+pub struct CommandArgs {
+    positional: Vec<String>,
+    /// Each option passed, with its value if it takes one.
+    flags: Vec<(String, Option<String>)>,
+}
+
+impl CommandArgs {
+    /// Parse `args` for a command whose options are `value_flags`, which take
+    /// a value (e.g. `--count 10`), and `switches` (e.g. `--verify`). Any
+    /// other `--` argument is an error.
+    pub fn parse(args: &[String], value_flags: &[&str], switches: &[&str]) -> Result<Self, String> {
+        let mut command_args = Self::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if value_flags.contains(&arg.as_str()) {
+                let value = args.next().ok_or_else(|| format!("{arg} needs a value"))?;
+                command_args.flags.push((arg.clone(), Some(value.clone())));
+            } else if switches.contains(&arg.as_str()) {
+                command_args.flags.push((arg.clone(), None));
+            } else if arg.starts_with("--") {
+                return Err(format!("unknown option: {arg}"));
+            } else {
+                command_args.positional.push(arg.clone());
+            }
+        }
+        Ok(command_args)
+    }
+
+    /// The arguments that aren't an option or an option's value.
+    pub fn positional(&self) -> Vec<&str> {
+        self.positional.iter().map(String::as_str).collect()
+    }
+
+    /// Returns `true` if the switch `flag` (e.g. `--verify`) was passed.
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|(name, _)| name == flag)
+    }
+
+    /// Returns the value given for `flag` (e.g. `--count 10`), if present.
+    pub fn flag_value(&self, flag: &str) -> Option<&str> {
+        self.flags
+            .iter()
+            .find(|(name, _)| name == flag)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    /// Parse the value given for `flag`, exiting with an error if it is
+    /// invalid.
+    pub fn parse_flag_value<T: FromStr>(&self, flag: &str) -> Option<T> {
+        parse_value(flag, self.flag_value(flag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_global_and_command_args() {
+        let global = GlobalArgs::parse_from(
+            [
+                "simd",
+                "0185",
+                "--output",
+                "json",
+                "sweep",
+                "--count",
+                "5",
+                "--simulate",
+                "--url",
+                "devnet",
+                "--dry-run",
+            ]
+            .map(String::from),
+        );
+        assert_eq!(global.program_name, "simd");
+        assert_eq!(global.url.as_deref(), Some("devnet"));
+        assert_eq!(global.output, OutputFormat::Json);
+        assert_eq!(global.commitment, CommitmentConfig::confirmed());
+        assert!(global.simulate);
+        assert!(!global.force);
+        assert_eq!(global.rest, ["0185", "sweep", "--count", "5", "--dry-run"]);
+
+        let args =
+            CommandArgs::parse(&global.rest, &["--count", "--file"], &["--dry-run"]).unwrap();
+        assert_eq!(args.positional(), ["0185", "sweep"]);
+        assert_eq!(args.parse_flag_value::<usize>("--count"), Some(5));
+        assert_eq!(args.flag_value("--file"), None);
+        assert!(args.has_flag("--dry-run"));
+        assert!(!args.has_flag("--count-all"));
+    }
+
+    #[test]
+    fn reject_unknown_options() {
+        let global =
+            GlobalArgs::parse_from(["simd", "0185", "create", "--simualte"].map(String::from));
+        assert!(!global.simulate);
+        assert_eq!(
+            CommandArgs::parse(&global.rest, &[], &[]).unwrap_err(),
+            "unknown option: --simualte"
+        );
+        // Options are only accepted by the commands that declare them.
+        let hex = ["--hex", "ab"].map(String::from);
+        assert!(CommandArgs::parse(&hex, &["--hex"], &[]).is_ok());
+        assert_eq!(
+            CommandArgs::parse(&hex, &[], &[]).unwrap_err(),
+            "unknown option: --hex"
+        );
+        assert_eq!(
+            CommandArgs::parse(&["--count".to_string()], &["--count"], &[]).unwrap_err(),
+            "--count needs a value"
+        );
+    }
+}
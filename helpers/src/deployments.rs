@@ -0,0 +1,355 @@
+//! Program ID resolution and the per-network deployments manifest.
+//!
+//! A program's ID is taken from, in order:
+//!
+//! 1. `--program-id <pubkey>`
+//! 2. `SIMD_XXXX_PROGRAM_ID`, e.g. `SIMD_0185_PROGRAM_ID` for `simd-0185`
+//! 3. `deployments.toml` at the workspace root, for the current network
+//! 4. `<program>/keypair.json` at the workspace root
+//!
+//! The `deploy` binary writes the manifest after each deployment, and its
+//! `--verify` mode compares the ELF each cluster is running against the local
+//! `target/deploy` build.
+
+use {
+    crate::{read_keypair_file, RpcClient, Signer},
+    serde::{Deserialize, Serialize},
+    sha2::{Digest, Sha256},
+    solana_loader_v3_interface::state::UpgradeableLoaderState,
+    solana_pubkey::Pubkey,
+    std::{collections::BTreeMap, fmt, fs, io, path::PathBuf, str::FromStr},
+};
+
+/// File name of the manifest at the workspace root.
+pub const MANIFEST_FILE: &str = "deployments.toml";
+
+/// Written above the entries whenever the manifest is saved.
+const MANIFEST_HEADER: &str = "\
+# Program deployments per network, written by `make deploy-<prog>` and read by
+# the `simd` client to find each program's ID. Entries look like:
+#
+# [simd-0185.testnet]
+# program_id = \"<pubkey>\"
+";
+
+/// One program's deployment on one network. Only `program_id` is required;
+/// the rest is recorded by the `deploy` binary.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deployment {
+    pub program_id: String,
+    /// Slot the program was last deployed or upgraded in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot: Option<u64>,
+    /// `None` once the program is immutable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upgrade_authority: Option<String>,
+    /// See `elf_sha256`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elf_sha256: Option<String>,
+}
+
+/// Deployments keyed by program (e.g. `simd-0185`), then by network (e.g.
+/// `testnet`):
+///
+/// ```toml
+/// [simd-0185.testnet]
+/// program_id = "..."
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Deployments(pub BTreeMap<String, BTreeMap<String, Deployment>>);
+
+impl Deployments {
+    pub fn path() -> PathBuf {
+        crate::workspace_root().join(MANIFEST_FILE)
+    }
+
+    /// Load the manifest, or an empty one if it doesn't exist.
+    pub fn load() -> Result<Self, String> {
+        let path = Self::path();
+        match fs::read_to_string(&path) {
+            Ok(contents) => Self::from_str(&contents)
+                .map_err(|err| format!("failed to parse {}: {err}", path.display())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(format!("failed to read {}: {err}", path.display())),
+        }
+    }
+
+    /// Write the manifest back to disk.
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path();
+        let entries = toml::to_string(self)
+            .map_err(|err| format!("failed to serialize {MANIFEST_FILE}: {err}"))?;
+        fs::write(&path, format!("{MANIFEST_HEADER}\n{entries}"))
+            .map_err(|err| format!("failed to write {}: {err}", path.display()))
+    }
+
+    pub fn get(&self, program: &str, network: &str) -> Option<&Deployment> {
+        self.0.get(program)?.get(network)
+    }
+
+    pub fn insert(&mut self, program: &str, network: &str, deployment: Deployment) {
+        self.0
+            .entry(program.to_string())
+            .or_default()
+            .insert(network.to_string(), deployment);
+    }
+}
+
+impl FromStr for Deployments {
+    type Err = toml::de::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s)
+    }
+}
+
+/// Where a resolved program ID came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProgramIdSource {
+    Flag,
+    EnvVar(String),
+    Manifest(String),
+    Keypair(PathBuf),
+}
+
+impl fmt::Display for ProgramIdSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Flag => write!(f, "--program-id"),
+            Self::EnvVar(name) => write!(f, "${name}"),
+            Self::Manifest(network) => write!(f, "{MANIFEST_FILE} ({network})"),
+            Self::Keypair(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// The environment variable overriding `program`'s ID, e.g.
+/// `SIMD_0185_PROGRAM_ID` for `simd-0185`.
+pub fn program_id_env_var(program: &str) -> String {
+    format!("{}_PROGRAM_ID", program.to_uppercase().replace('-', "_"))
+}
+
+/// Resolve `program`'s ID (e.g. for `simd-0185`) on `network` (see
+/// `crate::network_name`), unless `--program-id` gave it as `flag`.
+pub fn resolve_program_id(
+    program: &str,
+    network: &str,
+    flag: Option<Pubkey>,
+) -> Result<(Pubkey, ProgramIdSource), String> {
+    if let Some(program_id) = flag {
+        return Ok((program_id, ProgramIdSource::Flag));
+    }
+
+    let env_var = program_id_env_var(program);
+    if let Ok(value) = std::env::var(&env_var) {
+        let program_id =
+            Pubkey::from_str(&value).map_err(|err| format!("invalid ${env_var}: {err}"))?;
+        return Ok((program_id, ProgramIdSource::EnvVar(env_var)));
+    }
+
+    if let Some(deployment) = Deployments::load()?.get(program, network) {
+        let program_id = Pubkey::from_str(&deployment.program_id).map_err(|err| {
+            format!("invalid program_id for {program} on {network} in {MANIFEST_FILE}: {err}")
+        })?;
+        return Ok((program_id, ProgramIdSource::Manifest(network.to_string())));
+    }
+
+    let keypair_path = crate::workspace_root().join(program).join("keypair.json");
+    let keypair = read_keypair_file(&keypair_path).map_err(|err| {
+        format!(
+            "no program ID for {program}: pass --program-id, set ${env_var}, add it to \
+             {MANIFEST_FILE}, or create {} ({err})",
+            keypair_path.display()
+        )
+    })?;
+    Ok((keypair.pubkey(), ProgramIdSource::Keypair(keypair_path)))
+}
+
+/// Every `simd-*` program in the workspace, sorted.
+pub fn workspace_programs() -> Vec<String> {
+    let mut programs = fs::read_dir(crate::workspace_root())
+        .expect("failed to read workspace root")
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let name = entry.file_name().into_string().ok()?;
+            (name.starts_with("simd-") && entry.path().join("Cargo.toml").exists()).then_some(name)
+        })
+        .collect::<Vec<_>>();
+    programs.sort();
+    programs
+}
+
+/// The IDs the workspace programs resolve to on `network`, skipping any
+/// that don't resolve. `flag` is as for `resolve_program_id`.
+pub fn workspace_program_ids(network: &str, flag: Option<Pubkey>) -> Vec<Pubkey> {
+    workspace_programs()
+        .iter()
+        .filter_map(|program| resolve_program_id(program, network, flag).ok())
+        .map(|(program_id, _)| program_id)
+        .collect()
+}
+
+/// The local build of `program`, e.g. `target/deploy/simd_0185.so` for
+/// `simd-0185`.
+pub fn local_elf_path(program: &str) -> PathBuf {
+    crate::workspace_root()
+        .join("target/deploy")
+        .join(format!("{}.so", program.replace('-', "_")))
+}
+
+/// The hex SHA-256 of an ELF, ignoring trailing zeros so that a program data
+/// account allocated larger than the ELF (`--max-len`) hashes the same as the
+/// file it was deployed from.
+pub fn elf_sha256(elf: &[u8]) -> String {
+    let len = elf.iter().rposition(|byte| *byte != 0).map_or(0, |i| i + 1);
+    Sha256::digest(&elf[..len])
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// An upgradeable program as it exists on chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OnChainProgram {
+    pub slot: u64,
+    pub upgrade_authority: Option<Pubkey>,
+    /// The program data after its metadata, including any zero padding.
+    pub elf: Vec<u8>,
+}
+
+impl OnChainProgram {
+    /// Fetch `program_id` and its program data account. Returns `None` if the
+    /// program doesn't exist.
+    pub fn fetch(client: &RpcClient, program_id: &Pubkey) -> Result<Option<Self>, String> {
+        let Some(program) = fetch_account_data(client, program_id)? else {
+            return Ok(None);
+        };
+        let programdata_address = match bincode::deserialize(&program) {
+            Ok(UpgradeableLoaderState::Program {
+                programdata_address,
+            }) => programdata_address,
+            _ => return Err(format!("{program_id} is not an upgradeable program")),
+        };
+
+        let programdata = fetch_account_data(client, &programdata_address)?
+            .ok_or_else(|| format!("program data account {programdata_address} not found"))?;
+        let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+        match bincode::deserialize(&programdata) {
+            Ok(UpgradeableLoaderState::ProgramData {
+                slot,
+                upgrade_authority_address,
+            }) if programdata.len() >= metadata_len => Ok(Some(Self {
+                slot,
+                upgrade_authority: upgrade_authority_address,
+                elf: programdata[metadata_len..].to_vec(),
+            })),
+            _ => Err(format!(
+                "{programdata_address} is not a program data account"
+            )),
+        }
+    }
+
+    /// The manifest entry for this program at `program_id`.
+    pub fn deployment(&self, program_id: &Pubkey) -> Deployment {
+        Deployment {
+            program_id: program_id.to_string(),
+            slot: Some(self.slot),
+            upgrade_authority: self.upgrade_authority.map(|key| key.to_string()),
+            elf_sha256: Some(elf_sha256(&self.elf)),
+        }
+    }
+}
+
+/// The data of an account owned by the upgradeable loader, or `None` if the
+/// account doesn't exist.
+fn fetch_account_data(client: &RpcClient, address: &Pubkey) -> Result<Option<Vec<u8>>, String> {
+    let account = client
+        .get_account_with_commitment(address, client.commitment())
+        .map_err(|err| format!("failed to fetch {address}: {err}"))?
+        .value;
+    match account {
+        None => Ok(None),
+        Some(account) if account.owner == solana_sdk_ids::bpf_loader_upgradeable::id() => {
+            Ok(Some(account.data))
+        }
+        Some(account) => Err(format!(
+            "{address} is owned by {}, not the upgradeable loader",
+            account.owner
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_manifest() {
+        let program_id = Pubkey::new_unique();
+        let deployments = Deployments::from_str(&format!(
+            "[simd-0185.testnet]\nprogram_id = \"{program_id}\"\n"
+        ))
+        .unwrap();
+
+        assert_eq!(
+            deployments.get("simd-0185", "testnet"),
+            Some(&Deployment {
+                program_id: program_id.to_string(),
+                ..Deployment::default()
+            })
+        );
+        assert_eq!(deployments.get("simd-0185", "devnet"), None);
+        assert_eq!(deployments.get("simd-0387", "testnet"), None);
+    }
+
+    #[test]
+    fn manifest_roundtrip() {
+        let mut deployments = Deployments::default();
+        deployments.insert(
+            "simd-0185",
+            "http://127.0.0.1:8899",
+            Deployment {
+                program_id: Pubkey::new_unique().to_string(),
+                slot: Some(42),
+                upgrade_authority: Some(Pubkey::new_unique().to_string()),
+                elf_sha256: Some(elf_sha256(b"\x7fELF")),
+            },
+        );
+        deployments.insert(
+            "simd-0185",
+            "testnet",
+            Deployment {
+                program_id: Pubkey::new_unique().to_string(),
+                slot: Some(7),
+                ..Deployment::default()
+            },
+        );
+
+        let serialized = format!(
+            "{MANIFEST_HEADER}\n{}",
+            toml::to_string(&deployments).unwrap()
+        );
+        assert_eq!(Deployments::from_str(&serialized).unwrap(), deployments);
+    }
+
+    #[test]
+    fn elf_hash_ignores_zero_padding() {
+        let elf = b"\x7fELF\x00\x01".to_vec();
+        let mut padded = elf.clone();
+        padded.resize(64, 0);
+
+        assert_eq!(elf_sha256(&elf), elf_sha256(&padded));
+        assert_ne!(elf_sha256(&elf), elf_sha256(b"\x7fELF\x00\x02"));
+        // SHA-256 of the empty string.
+        assert_eq!(
+            elf_sha256(&[0; 8]),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn env_var_name() {
+        assert_eq!(program_id_env_var("simd-0185"), "SIMD_0185_PROGRAM_ID");
+    }
+}
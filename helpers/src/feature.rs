@@ -0,0 +1,184 @@
+//! Feature gate lookups against a live cluster.
+
+use {
+    solana_account::Account, solana_client::rpc_client::RpcClient,
+    solana_epoch_schedule::EpochSchedule, solana_pubkey::Pubkey,
+};
+
+/// Features exercised by the programs in this workspace.
+pub const SIMD_FEATURES: &[(&str, Pubkey)] = &[
+    ("SIMD-0185", agave_feature_set::vote_state_v4::ID),
+    (
+        "SIMD-0321",
+        agave_feature_set::provide_instruction_data_offset_in_vm_r2::ID,
+    ),
+    (
+        "SIMD-0387",
+        agave_feature_set::bls_pubkey_management_in_vote_account::ID,
+    ),
+];
+
+/// `getMultipleAccounts` accepts at most 100 pubkeys per request.
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// On-chain status of a feature gate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeatureStatus {
+    /// No feature account exists.
+    Inactive,
+    /// The feature account exists and will activate at the next epoch
+    /// boundary.
+    Pending,
+    /// The feature was activated at `slot`, in `epoch`.
+    Active { slot: u64, epoch: u64 },
+}
+
+impl FeatureStatus {
+    pub fn from_account(account: Option<&Account>, epoch_schedule: &EpochSchedule) -> Self {
+        match account.and_then(solana_feature_gate_interface::from_account) {
+            None => Self::Inactive,
+            Some(feature) => match feature.activated_at {
+                None => Self::Pending,
+                Some(slot) => Self::Active {
+                    slot,
+                    epoch: epoch_schedule.get_epoch(slot),
+                },
+            },
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Active { .. })
+    }
+}
+
+impl std::fmt::Display for FeatureStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Inactive => write!(f, "inactive"),
+            Self::Pending => write!(f, "pending activation"),
+            Self::Active { slot, epoch } => write!(f, "active since slot {slot} (epoch {epoch})"),
+        }
+    }
+}
+
+/// Look up the human-readable description of a feature, if known.
+pub fn feature_name(feature_id: &Pubkey) -> Option<&'static str> {
+    agave_feature_set::FEATURE_NAMES.get(feature_id).copied()
+}
+
+/// Fetch the status of a single feature gate.
+pub fn get_feature_status(client: &RpcClient, feature_id: &Pubkey) -> FeatureStatus {
+    get_feature_statuses(client, &[*feature_id])[0]
+}
+
+/// Fetch the status of several feature gates, batching account lookups.
+pub fn get_feature_statuses(client: &RpcClient, feature_ids: &[Pubkey]) -> Vec<FeatureStatus> {
+    let epoch_schedule = client
+        .get_epoch_schedule()
+        .expect("failed to fetch epoch schedule");
+
+    feature_ids
+        .chunks(MAX_MULTIPLE_ACCOUNTS)
+        .flat_map(|chunk| {
+            client
+                .get_multiple_accounts(chunk)
+                .expect("failed to fetch feature accounts")
+        })
+        .map(|account| FeatureStatus::from_account(account.as_ref(), &epoch_schedule))
+        .collect()
+}
+
+/// Best-effort lookup of the slot at which a pending feature account was
+/// created, taken from the oldest signature touching it.
+pub fn get_pending_since_slot(client: &RpcClient, feature_id: &Pubkey) -> Option<u64> {
+    client
+        .get_signatures_for_address(feature_id)
+        .ok()?
+        .last()
+        .map(|status| status.slot)
+}
+
+/// Exit the process unless `feature_id` is active on the cluster behind
+/// `client`. With `force`, print a warning and continue instead, e.g. for
+/// negative tests.
+pub fn require_feature(client: &RpcClient, feature_id: &Pubkey, force: bool) {
+    let status = get_feature_status(client, feature_id);
+    if status.is_active() {
+        return;
+    }
+
+    let detail = match status {
+        FeatureStatus::Pending => match get_pending_since_slot(client, feature_id) {
+            Some(slot) => format!("pending since slot {slot}"),
+            None => "pending activation".to_string(),
+        },
+        _ => "no feature account".to_string(),
+    };
+    let name = feature_name(feature_id).unwrap_or("unknown feature");
+    let message = format!(
+        "feature {} ({}) is not active on {} ({})",
+        feature_id,
+        name,
+        client.url(),
+        detail,
+    );
+
+    if force {
+        eprintln!("Warning: {message}");
+        eprintln!("Continuing anyway (--force)");
+        eprintln!();
+    } else {
+        eprintln!("Error: {message}");
+        eprintln!("Pass --force to send anyway");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_feature_gate_interface::{create_account, Feature},
+    };
+
+    #[test]
+    fn status_from_account() {
+        let epoch_schedule = EpochSchedule::without_warmup();
+
+        assert_eq!(
+            FeatureStatus::from_account(None, &epoch_schedule),
+            FeatureStatus::Inactive,
+        );
+
+        let pending = create_account(&Feature { activated_at: None }, 1).into();
+        assert_eq!(
+            FeatureStatus::from_account(Some(&pending), &epoch_schedule),
+            FeatureStatus::Pending,
+        );
+
+        let slot = epoch_schedule.slots_per_epoch * 3 + 1;
+        let active = create_account(
+            &Feature {
+                activated_at: Some(slot),
+            },
+            1,
+        )
+        .into();
+        assert_eq!(
+            FeatureStatus::from_account(Some(&active), &epoch_schedule),
+            FeatureStatus::Active { slot, epoch: 3 },
+        );
+    }
+
+    #[test]
+    fn status_wrong_owner() {
+        let epoch_schedule = EpochSchedule::without_warmup();
+        let mut account: Account = create_account(&Feature::default(), 1).into();
+        account.owner = Pubkey::new_unique();
+        assert_eq!(
+            FeatureStatus::from_account(Some(&account), &epoch_schedule),
+            FeatureStatus::Inactive,
+        );
+    }
+}
@@ -0,0 +1,336 @@
+//! Keypairs generated by client runs, kept so the accounts they control can
+//! be reused, e.g. a vote account created by `0185 create` and its authorized
+//! voter for the SIMD-0387 BLS flow.
+//!
+//! Each run gets its own directory, `scripts/out/runs/<timestamp>/`, holding
+//! a `<role>.json` keypair file per role and an index of the run:
+//!
+//! ```json
+//! {
+//!   "network": "testnet",
+//!   "command": "0185 create",
+//!   "keypairs": {
+//!     "vote_account": "<pubkey>",
+//!     "authorized_voter": "<pubkey>",
+//!     "authorized_withdrawer": "<pubkey>"
+//!   }
+//! }
+//! ```
+//!
+//! The timestamp is in milliseconds since the Unix epoch, so later runs sort
+//! after earlier ones.
+
+use {
+    crate::{read_keypair_file, write_keypair_file, Keypair, Signer},
+    serde::{Deserialize, Serialize},
+    solana_pubkey::Pubkey,
+    std::{
+        collections::BTreeMap,
+        fmt, fs, io,
+        path::{Path, PathBuf},
+        str::FromStr,
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// File name of the index in each run directory.
+pub const INDEX_FILE: &str = "index.json";
+
+/// What a saved keypair is for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    VoteAccount,
+    AuthorizedVoter,
+    AuthorizedWithdrawer,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::VoteAccount => write!(f, "vote_account"),
+            Self::AuthorizedVoter => write!(f, "authorized_voter"),
+            Self::AuthorizedWithdrawer => write!(f, "authorized_withdrawer"),
+        }
+    }
+}
+
+/// The index of a run: where and by what it was made, and the pubkey saved
+/// for each role.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunIndex {
+    /// See `crate::network_name`.
+    pub network: String,
+    /// The client command, e.g. `0185 create`.
+    pub command: String,
+    #[serde(default)]
+    pub keypairs: BTreeMap<Role, String>,
+}
+
+/// One run's directory and index.
+#[derive(Clone, Debug)]
+pub struct Run {
+    dir: PathBuf,
+    index: RunIndex,
+}
+
+impl Run {
+    fn load(dir: PathBuf) -> Result<Self, String> {
+        let path = dir.join(INDEX_FILE);
+        let contents = fs::read_to_string(&path)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        let index = serde_json::from_str(&contents)
+            .map_err(|err| format!("failed to parse {}: {err}", path.display()))?;
+        Ok(Self { dir, index })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn index(&self) -> &RunIndex {
+        &self.index
+    }
+
+    /// Write `keypair` as `role`'s keypair file and record it in the index.
+    /// The index is rewritten on every save, so a run that fails partway
+    /// still keeps what it generated.
+    pub fn save(&mut self, role: Role, keypair: &Keypair) -> Result<PathBuf, String> {
+        let path = self.keypair_path(role);
+        write_keypair_file(keypair, &path)
+            .map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+        self.index
+            .keypairs
+            .insert(role, keypair.pubkey().to_string());
+        self.write_index()?;
+        Ok(path)
+    }
+
+    /// The pubkey saved for `role`, if any.
+    pub fn pubkey(&self, role: Role) -> Option<Pubkey> {
+        Pubkey::from_str(self.index.keypairs.get(&role)?).ok()
+    }
+
+    /// Read the keypair saved for `role`.
+    pub fn keypair(&self, role: Role) -> Result<Keypair, String> {
+        if !self.index.keypairs.contains_key(&role) {
+            return Err(format!("{} has no {role} keypair", self.dir.display()));
+        }
+        let path = self.keypair_path(role);
+        read_keypair_file(&path).map_err(|err| format!("failed to read {}: {err}", path.display()))
+    }
+
+    /// Delete the run's directory, e.g. once the transaction that would
+    /// have created its accounts has definitely failed.
+    pub fn remove(self) -> Result<(), String> {
+        fs::remove_dir_all(&self.dir)
+            .map_err(|err| format!("failed to remove {}: {err}", self.dir.display()))
+    }
+
+    fn keypair_path(&self, role: Role) -> PathBuf {
+        self.dir.join(format!("{role}.json"))
+    }
+
+    fn write_index(&self) -> Result<(), String> {
+        let path = self.dir.join(INDEX_FILE);
+        let mut json =
+            serde_json::to_string_pretty(&self.index).expect("failed to serialize run index");
+        json.push('\n');
+        fs::write(&path, json).map_err(|err| format!("failed to write {}: {err}", path.display()))
+    }
+}
+
+/// The directory run directories are created in.
+#[derive(Clone, Debug)]
+pub struct Keystore {
+    root: PathBuf,
+}
+
+impl Default for Keystore {
+    /// `scripts/out/runs/` at the workspace root.
+    fn default() -> Self {
+        Self::new(crate::scripts_out_dir().join("runs"))
+    }
+}
+
+impl Keystore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Start a run of `command` on `network` in a new directory named after
+    /// the current time.
+    pub fn create_run(&self, network: &str, command: &str) -> Result<Run, String> {
+        fs::create_dir_all(&self.root)
+            .map_err(|err| format!("failed to create {}: {err}", self.root.display()))?;
+
+        let mut timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock is before the Unix epoch")
+            .as_millis();
+        // Runs started in the same millisecond take the next free one.
+        let dir = loop {
+            let dir = self.root.join(timestamp.to_string());
+            match fs::create_dir(&dir) {
+                Ok(()) => break dir,
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => timestamp += 1,
+                Err(err) => return Err(format!("failed to create {}: {err}", dir.display())),
+            }
+        };
+
+        let run = Run {
+            dir,
+            index: RunIndex {
+                network: network.to_string(),
+                command: command.to_string(),
+                keypairs: BTreeMap::new(),
+            },
+        };
+        run.write_index()?;
+        Ok(run)
+    }
+
+    /// Every run with an index, oldest first. Directories that aren't named
+    /// after a timestamp are skipped, and so are runs whose index can't be
+    /// read, with a warning.
+    pub fn runs(&self) -> Result<Vec<Run>, String> {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(format!("failed to read {}: {err}", self.root.display())),
+        };
+
+        let mut runs = Vec::new();
+        for entry in entries {
+            let entry =
+                entry.map_err(|err| format!("failed to read {}: {err}", self.root.display()))?;
+            let Some(timestamp) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse::<u128>().ok())
+            else {
+                continue;
+            };
+            if !entry.path().join(INDEX_FILE).is_file() {
+                continue;
+            }
+            match Run::load(entry.path()) {
+                Ok(run) => runs.push((timestamp, run)),
+                Err(err) => eprintln!("Warning: skipping run: {err}"),
+            }
+        }
+        runs.sort_by_key(|(timestamp, _)| *timestamp);
+        Ok(runs.into_iter().map(|(_, run)| run).collect())
+    }
+
+    /// The latest run on `network` that saved a keypair for every one of
+    /// `roles`.
+    pub fn latest(&self, network: &str, roles: &[Role]) -> Result<Option<Run>, String> {
+        Ok(self.runs()?.into_iter().rev().find(|run| {
+            run.index.network == network
+                && roles
+                    .iter()
+                    .all(|role| run.index.keypairs.contains_key(role))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_and_load_run() {
+        let root = tempfile::tempdir().unwrap();
+        let keystore = Keystore::new(root.path());
+        let vote_account = Keypair::new();
+        let authorized_voter = Keypair::new();
+
+        let mut run = keystore.create_run("testnet", "0185 create").unwrap();
+        run.save(Role::VoteAccount, &vote_account).unwrap();
+        run.save(Role::AuthorizedVoter, &authorized_voter).unwrap();
+
+        let runs = keystore.runs().unwrap();
+        assert_eq!(runs.len(), 1);
+        let run = &runs[0];
+        assert_eq!(run.index().network, "testnet");
+        assert_eq!(run.index().command, "0185 create");
+        assert_eq!(run.pubkey(Role::VoteAccount), Some(vote_account.pubkey()));
+        assert_eq!(
+            run.keypair(Role::AuthorizedVoter).unwrap().pubkey(),
+            authorized_voter.pubkey()
+        );
+        assert_eq!(run.pubkey(Role::AuthorizedWithdrawer), None);
+        assert!(run.keypair(Role::AuthorizedWithdrawer).is_err());
+
+        // Roles are keyed by name in the index.
+        let index = fs::read_to_string(run.dir().join(INDEX_FILE)).unwrap();
+        assert!(index.contains(&format!(
+            "\"authorized_voter\": \"{}\"",
+            authorized_voter.pubkey()
+        )));
+    }
+
+    #[test]
+    fn latest_run_by_network_and_roles() {
+        let root = tempfile::tempdir().unwrap();
+        let keystore = Keystore::new(root.path());
+        assert!(keystore
+            .latest("testnet", &[Role::VoteAccount])
+            .unwrap()
+            .is_none());
+
+        let save = |network: &str, roles: &[Role]| {
+            let mut run = keystore.create_run(network, "0185 create").unwrap();
+            for role in roles {
+                run.save(*role, &Keypair::new()).unwrap();
+            }
+            run.dir().to_path_buf()
+        };
+        let full = save(
+            "testnet",
+            &[
+                Role::VoteAccount,
+                Role::AuthorizedVoter,
+                Role::AuthorizedWithdrawer,
+            ],
+        );
+        let voterless = save("testnet", &[Role::VoteAccount]);
+        let devnet = save("devnet", &[Role::VoteAccount, Role::AuthorizedVoter]);
+        // Not a run directory.
+        fs::create_dir(root.path().join("scratch")).unwrap();
+
+        let latest = |network, roles: &[Role]| {
+            keystore
+                .latest(network, roles)
+                .unwrap()
+                .map(|run| run.dir().to_path_buf())
+        };
+        assert_eq!(latest("testnet", &[Role::VoteAccount]), Some(voterless));
+        assert_eq!(
+            latest("testnet", &[Role::VoteAccount, Role::AuthorizedVoter]),
+            Some(full)
+        );
+        assert_eq!(latest("devnet", &[Role::VoteAccount]), Some(devnet.clone()));
+        assert_eq!(latest("mainnet", &[]), None);
+
+        // A run with a corrupt index doesn't hide the others.
+        let corrupt = keystore.create_run("devnet", "0185 create").unwrap();
+        fs::write(corrupt.dir().join(INDEX_FILE), "{").unwrap();
+        assert_eq!(keystore.runs().unwrap().len(), 3);
+        assert_eq!(latest("devnet", &[Role::VoteAccount]), Some(devnet));
+    }
+
+    #[test]
+    fn remove_run() {
+        let root = tempfile::tempdir().unwrap();
+        let keystore = Keystore::new(root.path());
+        let mut run = keystore.create_run("testnet", "0185 create").unwrap();
+        run.save(Role::VoteAccount, &Keypair::new()).unwrap();
+        let dir = run.dir().to_path_buf();
+
+        run.remove().unwrap();
+        assert!(!dir.exists());
+        assert!(keystore.runs().unwrap().is_empty());
+    }
+}
@@ -1,15 +1,52 @@
-use solana_cli_config::CONFIG_FILE;
+pub mod args;
+pub mod deployments;
+pub mod feature;
+pub mod keystore;
+pub mod report;
+pub mod vote;
+
 pub use {
+    args::{CommandArgs, GlobalArgs},
+    report::{OutputFormat, TransactionReport},
     solana_cli_config::Config,
     solana_client::{
+        client_error::ClientError,
         rpc_client::RpcClient,
-        rpc_config::{RpcTransactionConfig, UiTransactionEncoding},
-        rpc_response::OptionSerializer,
+        rpc_config::{RpcSimulateTransactionConfig, RpcTransactionConfig, UiTransactionEncoding},
+        rpc_response::{OptionSerializer, TransactionError},
     },
     solana_commitment_config::CommitmentConfig,
-    solana_keypair::{read_keypair_file, Keypair, Signer},
+    solana_hash::Hash,
+    solana_instruction::error::InstructionError,
+    solana_keypair::{read_keypair_file, write_keypair_file, Keypair, Signer},
+    solana_pubkey::Pubkey,
     solana_transaction::{Signature, Transaction},
 };
+use {
+    solana_cli_config::CONFIG_FILE,
+    solana_client::{
+        client_error::ClientErrorKind,
+        rpc_request::{RpcError, RpcResponseErrorData},
+        rpc_response::{EncodedTransaction, RpcSimulateTransactionResult},
+    },
+    solana_transaction_status_client_types::{UiMessage, UiTransaction},
+};
+
+/// The network moniker for a well-known RPC URL, the inverse of
+/// `rpc_url_from_network`.
+pub fn network_from_rpc_url(rpc_url: &str) -> Option<&'static str> {
+    ["mainnet", "devnet", "testnet", "localnet"]
+        .into_iter()
+        .find(|network| rpc_url_from_network(network) == rpc_url.trim_end_matches('/'))
+}
+
+/// The key a cluster is recorded under in `deployments.toml`: its network
+/// moniker, or the RPC URL itself for any other cluster.
+pub fn network_name(rpc_url: &str) -> String {
+    network_from_rpc_url(rpc_url)
+        .map(str::to_string)
+        .unwrap_or_else(|| rpc_url.trim_end_matches('/').to_string())
+}
 
 pub fn rpc_url_from_network(network: &str) -> String {
     match network {
@@ -17,14 +54,30 @@ pub fn rpc_url_from_network(network: &str) -> String {
         "devnet" => "https://api.devnet.solana.com".to_string(),
         "testnet" => "https://api.testnet.solana.com".to_string(),
         "localnet" => "http://localhost:8899".to_string(),
+        url if url.starts_with("http://") || url.starts_with("https://") => url.to_string(),
         other => {
             eprintln!("Unknown network: {other}");
-            eprintln!("Expected: mainnet, devnet, testnet, or localnet");
+            eprintln!("Expected: mainnet, devnet, testnet, localnet, or an http(s) URL");
             std::process::exit(1);
         }
     }
 }
 
+/// The workspace root, so files can be found regardless of the current
+/// directory.
+pub fn workspace_root() -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("helpers lives in the workspace")
+        .to_path_buf()
+}
+
+/// The directory generated artifacts are written to, `scripts/out/` at the
+/// workspace root.
+pub fn scripts_out_dir() -> std::path::PathBuf {
+    workspace_root().join("scripts/out")
+}
+
 pub fn load_config() -> Config {
     let config_file = CONFIG_FILE
         .as_ref()
@@ -32,50 +85,73 @@ pub fn load_config() -> Config {
     Config::load(config_file).expect("failed to load Solana CLI config")
 }
 
-pub fn load_payer() -> Keypair {
-    let config = load_config();
-    read_keypair_file(&config.keypair_path).expect("failed to read payer keypair")
+/// Load the payer keypair from `--keypair`, or from the Solana CLI config.
+pub fn load_payer(global: &GlobalArgs) -> Keypair {
+    let keypair_path = global
+        .keypair
+        .clone()
+        .unwrap_or_else(|| load_config().keypair_path);
+    read_keypair_file(&keypair_path).expect("failed to read payer keypair")
 }
 
-/// Parse optional network arg from `env::args().nth(1)`, resolve the RPC URL,
-/// create an `RpcClient`, and load the payer keypair from the Solana CLI
-/// config.
-pub fn client_from_args() -> (RpcClient, Keypair) {
-    let config = load_config();
-    let payer = read_keypair_file(&config.keypair_path).expect("failed to read payer keypair");
-
-    let rpc_url = match std::env::args().nth(1) {
-        Some(network) => rpc_url_from_network(&network),
-        None => config.json_rpc_url.clone(),
-    };
-    println!("RPC URL: {}", rpc_url);
+/// Resolve `program`'s ID (e.g. for `simd-0185`) for the cluster `client`
+/// points at. See `deployments` for the lookup order.
+pub fn program_id(global: &GlobalArgs, client: &RpcClient, program: &str) -> Pubkey {
+    let network = network_name(&client.url());
+    match deployments::resolve_program_id(program, &network, global.program_id) {
+        Ok((program_id, source)) => {
+            status!(global, "Program ID: {} (from {})", program_id, source);
+            program_id
+        }
+        Err(err) => {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        }
+    }
+}
 
-    let client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
-    (client, payer)
+/// Take the optional network arg (the command's first positional argument),
+/// resolve the RPC URL, create an `RpcClient`, and load the payer keypair.
+/// `--url` and `--keypair` take precedence over the network arg and the Solana
+/// CLI config.
+pub fn client_from_args(global: &GlobalArgs, args: &CommandArgs) -> (RpcClient, Keypair) {
+    let network = args.positional().first().map(|network| network.to_string());
+    client_with_network_override(global, network)
 }
 
 /// Create an `RpcClient` and load the payer keypair. If `network_override` is
 /// provided, use that network; otherwise, use the default from the Solana CLI
 /// config.
-pub fn client_with_network_override(network_override: Option<String>) -> (RpcClient, Keypair) {
-    let payer = load_payer();
-
-    if let Some(network) = network_override {
-        let rpc_url = rpc_url_from_network(&network);
-        println!("RPC URL: {}", rpc_url);
-        let client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
-        (client, payer)
-    } else {
-        let config = load_config();
-        println!("RPC URL: {}", config.json_rpc_url);
-        let client =
-            RpcClient::new_with_commitment(config.json_rpc_url, CommitmentConfig::confirmed());
-        (client, payer)
-    }
+pub fn client_with_network_override(
+    global: &GlobalArgs,
+    network_override: Option<String>,
+) -> (RpcClient, Keypair) {
+    let payer = load_payer(global);
+    let client = rpc_client_with_network_override(global, network_override);
+    (client, payer)
 }
 
-/// Fetch and print transaction logs for a given signature.
-pub fn print_transaction_logs_for_signature(client: &RpcClient, signature: &Signature) {
+/// Create an `RpcClient` without loading a payer, for read-only tools. The
+/// RPC URL comes from `--url`, then `network_override`, then the Solana CLI
+/// config, and the commitment from `--commitment`.
+pub fn rpc_client_with_network_override(
+    global: &GlobalArgs,
+    network_override: Option<String>,
+) -> RpcClient {
+    let rpc_url = match global.url.clone().or(network_override) {
+        Some(network) => rpc_url_from_network(&network),
+        None => load_config().json_rpc_url,
+    };
+    status!(global, "RPC URL: {}", rpc_url);
+    RpcClient::new_with_commitment(rpc_url, global.commitment)
+}
+
+/// Fetch a confirmed transaction and build a report from its metadata.
+pub fn fetch_transaction_report(
+    global: &GlobalArgs,
+    client: &RpcClient,
+    signature: &Signature,
+) -> TransactionReport {
     let tx_response = client
         .get_transaction_with_config(
             signature,
@@ -86,13 +162,194 @@ pub fn print_transaction_logs_for_signature(client: &RpcClient, signature: &Sign
             },
         )
         .expect("failed to fetch transaction");
+    let meta = tx_response
+        .transaction
+        .meta
+        .expect("transaction has no status metadata");
+
+    let mut account_keys = match tx_response.transaction.transaction {
+        EncodedTransaction::Json(UiTransaction {
+            message: UiMessage::Raw(message),
+            ..
+        }) => message.account_keys,
+        _ => Vec::new(),
+    };
+    if let OptionSerializer::Some(loaded) = meta.loaded_addresses {
+        account_keys.extend(loaded.writable);
+        account_keys.extend(loaded.readonly);
+    }
+
+    let logs: Vec<String> = meta.log_messages.unwrap_or(Vec::new());
+    let inner_instructions = meta.inner_instructions.unwrap_or(Vec::new());
+
+    TransactionReport::new(
+        signature.to_string(),
+        false,
+        Some(tx_response.slot),
+        Some(meta.fee),
+        meta.compute_units_consumed.into(),
+        meta.err,
+        logs,
+        report::collect_inner_instructions(&inner_instructions, &account_keys),
+        &test_program_ids(global, client),
+    )
+}
+
+/// The IDs the workspace programs resolve to for the cluster `client` points
+/// at, so a report only decodes `TestProgramError`s that they raised.
+fn test_program_ids(global: &GlobalArgs, client: &RpcClient) -> Vec<Pubkey> {
+    deployments::workspace_program_ids(&network_name(&client.url()), global.program_id)
+}
+
+/// Fetch and print the report for a given signature, honoring `--output`.
+pub fn print_transaction_logs_for_signature(
+    global: &GlobalArgs,
+    client: &RpcClient,
+    signature: &Signature,
+) {
+    fetch_transaction_report(global, client, signature).print(global.output);
+}
+
+/// Send and confirm a transaction, or simulate it with `--simulate` so
+/// nothing lands on chain. Preflight failures are reported rather than
+/// panicking.
+pub fn execute_transaction(
+    global: &GlobalArgs,
+    client: &RpcClient,
+    tx: &Transaction,
+) -> TransactionReport {
+    try_execute_transaction(global, client, tx)
+        .unwrap_or_else(|err| panic!("transaction failed: {err}"))
+}
+
+/// Like `execute_transaction`, but returns RPC errors other than preflight
+/// failures instead of panicking.
+pub fn try_execute_transaction(
+    global: &GlobalArgs,
+    client: &RpcClient,
+    tx: &Transaction,
+) -> Result<TransactionReport, ClientError> {
+    if global.simulate {
+        return Ok(simulate_transaction(global, client, tx));
+    }
+
+    status!(global, "Sending transaction...");
+    match client.send_and_confirm_transaction(tx) {
+        Ok(signature) => Ok(fetch_transaction_report(global, client, &signature)),
+        Err(err) => match err.kind() {
+            ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+                ..
+            }) => Ok(report_from_simulation(
+                global,
+                client,
+                tx,
+                None,
+                result.clone(),
+                false,
+            )),
+            _ => Err(err),
+        },
+    }
+}
+
+/// Execute a transaction and print its report, honoring `--output`. Exits the
+/// process if the transaction failed.
+pub fn send_transaction(
+    global: &GlobalArgs,
+    client: &RpcClient,
+    tx: &Transaction,
+) -> TransactionReport {
+    let report = execute_transaction(global, client, tx);
+    status!(global);
+    report.print(global.output);
+    if !report.is_success() {
+        std::process::exit(1);
+    }
+    report
+}
+
+/// Simulate a transaction against the current bank.
+pub fn simulate_transaction(
+    global: &GlobalArgs,
+    client: &RpcClient,
+    tx: &Transaction,
+) -> TransactionReport {
+    status!(global, "Simulating transaction...");
+    let response = client
+        .simulate_transaction_with_config(
+            tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(CommitmentConfig::confirmed()),
+                inner_instructions: true,
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )
+        .expect("failed to simulate transaction");
+    report_from_simulation(
+        global,
+        client,
+        tx,
+        Some(response.context.slot),
+        response.value,
+        true,
+    )
+}
+
+/// `simulated` is `false` for a sent transaction rejected at preflight, whose
+/// report also comes from a simulation.
+fn report_from_simulation(
+    global: &GlobalArgs,
+    client: &RpcClient,
+    tx: &Transaction,
+    slot: Option<u64>,
+    result: RpcSimulateTransactionResult,
+    simulated: bool,
+) -> TransactionReport {
+    let account_keys: Vec<String> = tx
+        .message
+        .account_keys
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let logs = result.logs.unwrap_or_default();
+    let inner_instructions = result.inner_instructions.unwrap_or_default();
+
+    TransactionReport::new(
+        tx.signatures[0].to_string(),
+        simulated,
+        slot,
+        result.fee,
+        result.units_consumed,
+        result.err,
+        logs,
+        report::collect_inner_instructions(&inner_instructions, &account_keys),
+        &test_program_ids(global, client),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if let Some(meta) = tx_response.transaction.meta {
-        if let OptionSerializer::Some(logs) = meta.log_messages {
-            println!("Transaction logs:");
-            for log in &logs {
-                println!("  {}", log);
-            }
+    #[test]
+    fn network_rpc_url_roundtrip() {
+        for network in ["mainnet", "devnet", "testnet", "localnet"] {
+            assert_eq!(
+                network_from_rpc_url(&rpc_url_from_network(network)),
+                Some(network)
+            );
         }
+        assert_eq!(
+            network_from_rpc_url("https://api.testnet.solana.com/"),
+            Some("testnet")
+        );
+        assert_eq!(network_from_rpc_url("https://rpc.example.com"), None);
+        assert_eq!(
+            network_name("https://rpc.example.com/"),
+            "https://rpc.example.com"
+        );
     }
 }
@@ -0,0 +1,575 @@
+//! Structured transaction results.
+
+use {
+    program_error::TestProgramError,
+    serde::Serialize,
+    solana_client::rpc_response::{
+        TransactionError, UiInnerInstructions, UiInstruction, UiParsedInstruction,
+        UiTransactionError,
+    },
+    solana_instruction::error::InstructionError,
+    solana_pubkey::Pubkey,
+    std::{fmt, str::FromStr},
+};
+
+/// How to print results, selected with `--output <text|json>`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(format!("unknown output format: {other}")),
+        }
+    }
+}
+
+/// Print a progress line to stdout, or to stderr with `--output json` so
+/// stdout stays machine-readable. The first argument is the `GlobalArgs`.
+#[macro_export]
+macro_rules! status {
+    ($global:expr $(, $($arg:tt)*)?) => {
+        if $global.output == $crate::report::OutputFormat::Json {
+            eprintln!($($($arg)*)?);
+        } else {
+            println!($($($arg)*)?);
+        }
+    };
+}
+
+/// The outcome of a sent or simulated transaction.
+#[derive(Clone, Debug, Serialize)]
+pub struct TransactionReport {
+    pub signature: String,
+    pub simulated: bool,
+    /// `None` if the transaction failed preflight simulation.
+    pub slot: Option<u64>,
+    pub fee: Option<u64>,
+    pub compute_units_consumed: Option<u64>,
+    pub error: Option<UiTransactionError>,
+    /// The `TestProgramError` behind a `Custom(n)` error raised by one of the
+    /// test programs, if any. See `decode_program_error`.
+    pub program_error: Option<String>,
+    /// Logs split per top-level instruction, with CPIs nested inside.
+    pub instructions: Vec<InstructionLogs>,
+    pub inner_instructions: Vec<InnerInstruction>,
+    /// The raw log lines, as returned by the RPC.
+    pub logs: Vec<String>,
+}
+
+/// Logs emitted by one program invocation, delimited by
+/// `Program <id> invoke [n]` and `Program <id> success|failed`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct InstructionLogs {
+    pub program_id: String,
+    pub depth: usize,
+    pub logs: Vec<String>,
+    pub compute_units_consumed: Option<u64>,
+    /// `"success"` or the failure message. `None` if the logs were
+    /// truncated before the invocation finished.
+    pub result: Option<String>,
+    pub invocations: Vec<InstructionLogs>,
+}
+
+/// An instruction invoked via CPI, as recorded in the transaction metadata.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct InnerInstruction {
+    /// Index of the top-level instruction that made the CPI.
+    pub instruction_index: u8,
+    pub program_id: String,
+    pub stack_height: Option<u32>,
+    /// Base58-encoded instruction data, if available.
+    pub data: Option<String>,
+}
+
+impl TransactionReport {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        signature: String,
+        simulated: bool,
+        slot: Option<u64>,
+        fee: Option<u64>,
+        compute_units_consumed: Option<u64>,
+        error: Option<UiTransactionError>,
+        logs: Vec<String>,
+        inner_instructions: Vec<InnerInstruction>,
+        test_program_ids: &[Pubkey],
+    ) -> Self {
+        let mut report = Self {
+            signature,
+            simulated,
+            slot,
+            fee,
+            compute_units_consumed,
+            error,
+            program_error: None,
+            instructions: split_logs(&logs),
+            inner_instructions,
+            logs,
+        };
+        report.program_error = report
+            .decode_program_error(test_program_ids)
+            .map(|err| format!("{:?}", err));
+        report
+    }
+
+    /// The innermost failed invocation, i.e. the program that raised the
+    /// transaction's error.
+    pub fn failed_invocation(&self) -> Option<&InstructionLogs> {
+        let TransactionError::InstructionError(index, _) = self.error.clone()?.into() else {
+            return None;
+        };
+        self.instructions
+            .get(usize::from(index))?
+            .innermost_failure()
+    }
+
+    /// Decode a `Custom(n)` error back into a `TestProgramError`, if it was
+    /// raised by one of `test_program_ids` as a top-level instruction rather
+    /// than by a CPI callee or any other program.
+    pub fn decode_program_error(&self, test_program_ids: &[Pubkey]) -> Option<TestProgramError> {
+        let TransactionError::InstructionError(_, InstructionError::Custom(code)) =
+            self.error.clone()?.into()
+        else {
+            return None;
+        };
+        let failed = self.failed_invocation()?;
+        let is_test_program = test_program_ids
+            .iter()
+            .any(|id| id.to_string() == failed.program_id);
+        if failed.depth != 1 || !is_test_program {
+            return None;
+        }
+        TestProgramError::try_from(code).ok()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    pub fn print(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Text => print!("{self}"),
+            OutputFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(self).expect("failed to serialize report")
+            ),
+        }
+    }
+}
+
+impl fmt::Display for TransactionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.simulated {
+            writeln!(f, "Simulated transaction:  {}", self.signature)?;
+        } else {
+            writeln!(f, "Signature:              {}", self.signature)?;
+        }
+        if let Some(slot) = self.slot {
+            writeln!(f, "Slot:                   {}", slot)?;
+        }
+        if let Some(fee) = self.fee {
+            writeln!(f, "Fee:                    {} lamports", fee)?;
+        }
+        if let Some(units) = self.compute_units_consumed {
+            writeln!(f, "Compute units consumed: {}", units)?;
+        }
+        match &self.error {
+            Some(err) => match &self.program_error {
+                Some(name) => writeln!(f, "Status:                 failed: {} ({})", err, name)?,
+                None => writeln!(f, "Status:                 failed: {}", err)?,
+            },
+            None => writeln!(f, "Status:                 success")?,
+        }
+
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            writeln!(f)?;
+            write!(f, "Instruction #{}: ", index + 1)?;
+            instruction.fmt_tree(f, 0)?;
+        }
+
+        if !self.inner_instructions.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Inner instructions:")?;
+            for inner in &self.inner_instructions {
+                write!(
+                    f,
+                    "  #{} {}",
+                    u16::from(inner.instruction_index) + 1,
+                    inner.program_id
+                )?;
+                if let Some(stack_height) = inner.stack_height {
+                    write!(f, " (stack height {})", stack_height)?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl InstructionLogs {
+    fn innermost_failure(&self) -> Option<&InstructionLogs> {
+        if !self.result.as_deref()?.starts_with("failed") {
+            return None;
+        }
+        self.invocations
+            .iter()
+            .find_map(InstructionLogs::innermost_failure)
+            .or(Some(self))
+    }
+
+    fn fmt_tree(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        let pad = "  ".repeat(indent);
+        write!(f, "{}", self.program_id)?;
+        match (&self.result, self.compute_units_consumed) {
+            (Some(result), Some(units)) => writeln!(f, " ({}, {} CU)", result, units)?,
+            (Some(result), None) => writeln!(f, " ({})", result)?,
+            (None, _) => writeln!(f, " (logs truncated)")?,
+        }
+        for log in &self.logs {
+            writeln!(f, "{}  {}", pad, log)?;
+        }
+        for invocation in &self.invocations {
+            write!(f, "{}  > CPI: ", pad)?;
+            invocation.fmt_tree(f, indent + 2)?;
+        }
+        Ok(())
+    }
+}
+
+/// Split raw transaction logs into one entry per top-level instruction, with
+/// CPIs nested according to their `invoke [n]` depth.
+pub fn split_logs(logs: &[String]) -> Vec<InstructionLogs> {
+    let mut instructions = Vec::new();
+    let mut stack: Vec<InstructionLogs> = Vec::new();
+
+    fn finish(
+        stack: &mut Vec<InstructionLogs>,
+        instructions: &mut Vec<InstructionLogs>,
+        result: Option<String>,
+    ) {
+        if let Some(mut invocation) = stack.pop() {
+            invocation.result = result;
+            match stack.last_mut() {
+                Some(parent) => parent.invocations.push(invocation),
+                None => instructions.push(invocation),
+            }
+        }
+    }
+
+    for line in logs {
+        // `Program log:`, `Program data:` and `Program return:` lines are a
+        // program's own output, so only a program ID marks structure.
+        let Some((program_id, tail)) = line
+            .strip_prefix("Program ")
+            .and_then(|rest| rest.split_once(' '))
+            .filter(|(program_id, _)| Pubkey::from_str(program_id).is_ok())
+        else {
+            if let Some(top) = stack.last_mut() {
+                top.logs.push(line.clone());
+            }
+            continue;
+        };
+
+        if let Some(depth) = tail
+            .strip_prefix("invoke [")
+            .and_then(|rest| rest.strip_suffix(']'))
+            .and_then(|depth| depth.parse().ok())
+        {
+            stack.push(InstructionLogs {
+                program_id: program_id.to_string(),
+                depth,
+                ..InstructionLogs::default()
+            });
+        } else if tail == "success" {
+            finish(&mut stack, &mut instructions, Some(tail.to_string()));
+        } else if let Some(err) = tail.strip_prefix("failed: ") {
+            finish(
+                &mut stack,
+                &mut instructions,
+                Some(format!("failed: {err}")),
+            );
+        } else if let Some(units) = tail
+            .strip_prefix("consumed ")
+            .and_then(|rest| rest.split_once(' '))
+            .and_then(|(units, _)| units.parse().ok())
+        {
+            if let Some(top) = stack.last_mut() {
+                top.compute_units_consumed = Some(units);
+            }
+        } else if let Some(top) = stack.last_mut() {
+            top.logs.push(line.clone());
+        }
+    }
+
+    // Logs may be truncated mid-invocation.
+    while !stack.is_empty() {
+        finish(&mut stack, &mut instructions, None);
+    }
+
+    instructions
+}
+
+/// Flatten inner instructions from the transaction metadata, resolving
+/// program ID indexes against `account_keys`.
+pub fn collect_inner_instructions(
+    inner_instructions: &[UiInnerInstructions],
+    account_keys: &[String],
+) -> Vec<InnerInstruction> {
+    inner_instructions
+        .iter()
+        .flat_map(|inner| {
+            inner
+                .instructions
+                .iter()
+                .map(move |instruction| match instruction {
+                    UiInstruction::Compiled(compiled) => InnerInstruction {
+                        instruction_index: inner.index,
+                        program_id: account_keys
+                            .get(usize::from(compiled.program_id_index))
+                            .cloned()
+                            .unwrap_or_else(|| {
+                                format!("<account index {}>", compiled.program_id_index)
+                            }),
+                        stack_height: compiled.stack_height,
+                        data: Some(compiled.data.clone()),
+                    },
+                    UiInstruction::Parsed(UiParsedInstruction::Parsed(parsed)) => {
+                        InnerInstruction {
+                            instruction_index: inner.index,
+                            program_id: parsed.program_id.clone(),
+                            stack_height: parsed.stack_height,
+                            data: None,
+                        }
+                    }
+                    UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(decoded)) => {
+                        InnerInstruction {
+                            instruction_index: inner.index,
+                            program_id: decoded.program_id.clone(),
+                            stack_height: decoded.stack_height,
+                            data: Some(decoded.data.clone()),
+                        }
+                    }
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_client::rpc_response::UiCompiledInstruction};
+
+    /// Stands in for a test program's ID.
+    const PROG: &str = "AromLrvyg4CGtNjCacFf2utgRyvpSM3vRTt1v5Lg2h2E";
+
+    /// `logs` with the `Prog111` and `Vote111` placeholders replaced by
+    /// program IDs, since only lines naming a valid pubkey delimit
+    /// invocations.
+    fn lines(logs: &[&str]) -> Vec<String> {
+        logs.iter()
+            .map(|log| {
+                log.replace("Prog111", PROG)
+                    .replace("Vote111", &solana_sdk_ids::vote::ID.to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn split_nested_logs() {
+        let logs = lines(&[
+            "Program Prog111 invoke [1]",
+            "Program log: creating",
+            "Program 11111111111111111111111111111111 invoke [2]",
+            "Program 11111111111111111111111111111111 success",
+            "Program Vote111 invoke [2]",
+            "Program Vote111 success",
+            "Program Prog111 consumed 4200 of 200000 compute units",
+            "Program Prog111 success",
+            "Program Prog111 invoke [1]",
+            "Program log: Vote State (v4):",
+            "Program Prog111 consumed 900 of 195800 compute units",
+            "Program Prog111 failed: custom program error: 0x1",
+        ]);
+
+        let instructions = split_logs(&logs);
+        assert_eq!(instructions.len(), 2);
+
+        let create = &instructions[0];
+        assert_eq!(create.program_id, PROG);
+        assert_eq!(create.depth, 1);
+        assert_eq!(create.logs, lines(&["Program log: creating"]));
+        assert_eq!(create.compute_units_consumed, Some(4200));
+        assert_eq!(create.result.as_deref(), Some("success"));
+        assert_eq!(create.invocations.len(), 2);
+        assert_eq!(
+            create.invocations[0].program_id,
+            "11111111111111111111111111111111"
+        );
+        assert_eq!(
+            create.invocations[1].program_id,
+            solana_sdk_ids::vote::ID.to_string()
+        );
+        assert_eq!(create.invocations[1].depth, 2);
+
+        let view = &instructions[1];
+        assert_eq!(view.logs, lines(&["Program log: Vote State (v4):"]));
+        assert_eq!(
+            view.result.as_deref(),
+            Some("failed: custom program error: 0x1")
+        );
+        assert!(view.invocations.is_empty());
+    }
+
+    #[test]
+    fn split_truncated_logs() {
+        let logs = lines(&[
+            "Program Prog111 invoke [1]",
+            "Program Vote111 invoke [2]",
+            "Log truncated",
+        ]);
+
+        let instructions = split_logs(&logs);
+        assert_eq!(instructions.len(), 1);
+        assert_eq!(instructions[0].result, None);
+        assert_eq!(instructions[0].invocations[0].result, None);
+        assert_eq!(
+            instructions[0].invocations[0].logs,
+            lines(&["Log truncated"])
+        );
+    }
+
+    #[test]
+    fn split_logs_ignore_program_output() {
+        // Program output that looks like `Program <id> <result>`.
+        let logs = lines(&[
+            "Program Prog111 invoke [1]",
+            "Program log: success",
+            "Program Vote111 invoke [2]",
+            "Program log: failed: not really",
+            "Program data: AQID",
+            "Program Vote111 success",
+            "Program return: Prog111 AQID",
+            "Program Prog111 success",
+        ]);
+
+        let instructions = split_logs(&logs);
+        assert_eq!(instructions.len(), 1);
+        let instruction = &instructions[0];
+        assert_eq!(instruction.program_id, PROG);
+        assert_eq!(instruction.result.as_deref(), Some("success"));
+        assert_eq!(
+            instruction.logs,
+            lines(&["Program log: success", "Program return: Prog111 AQID"])
+        );
+        assert_eq!(instruction.invocations.len(), 1);
+        assert_eq!(
+            instruction.invocations[0].logs,
+            lines(&["Program log: failed: not really", "Program data: AQID"])
+        );
+    }
+
+    fn failed_report(error: TransactionError, logs: &[&str]) -> TransactionReport {
+        TransactionReport::new(
+            "sig".to_string(),
+            false,
+            Some(1),
+            Some(5000),
+            None,
+            Some(error.into()),
+            lines(logs),
+            Vec::new(),
+            &[Pubkey::from_str(PROG).unwrap()],
+        )
+    }
+
+    #[test]
+    fn decode_test_program_error() {
+        let report = failed_report(
+            TransactionError::InstructionError(0, InstructionError::Custom(5)),
+            &[
+                "Program Prog111 invoke [1]",
+                "Program Prog111 failed: custom program error: 0x5",
+            ],
+        );
+        assert_eq!(
+            report.decode_program_error(&[Pubkey::from_str(PROG).unwrap()]),
+            Some(TestProgramError::NotV4VoteState)
+        );
+        assert_eq!(report.program_error.as_deref(), Some("NotV4VoteState"));
+    }
+
+    #[test]
+    fn skip_other_program_errors() {
+        // Raised by the vote program via CPI.
+        let report = failed_report(
+            TransactionError::InstructionError(0, InstructionError::Custom(3)),
+            &[
+                "Program Prog111 invoke [1]",
+                "Program Vote111 invoke [2]",
+                "Program Vote111 failed: custom program error: 0x3",
+                "Program Prog111 failed: custom program error: 0x3",
+            ],
+        );
+        assert_eq!(
+            report.failed_invocation().unwrap().program_id,
+            solana_sdk_ids::vote::ID.to_string()
+        );
+        assert_eq!(report.program_error, None);
+
+        // Raised by the vote program as a top-level instruction.
+        let report = failed_report(
+            TransactionError::InstructionError(0, InstructionError::Custom(3)),
+            &[
+                "Program Vote111 invoke [1]",
+                "Program Vote111 failed: custom program error: 0x3",
+            ],
+        );
+        assert_eq!(report.program_error, None);
+
+        // Raised by any other on-chain program, e.g. SPL Token.
+        let report = failed_report(
+            TransactionError::InstructionError(0, InstructionError::Custom(5)),
+            &[
+                "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [1]",
+                "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA failed: custom program \
+                 error: 0x5",
+            ],
+        );
+        assert_eq!(report.failed_invocation().unwrap().depth, 1);
+        assert_eq!(report.program_error, None);
+    }
+
+    #[test]
+    fn resolve_inner_instructions() {
+        let account_keys = ["Payer111", "Vote111", "Prog111"].map(String::from);
+        let inner = [UiInnerInstructions {
+            index: 0,
+            instructions: vec![UiInstruction::Compiled(UiCompiledInstruction {
+                program_id_index: 1,
+                accounts: vec![0],
+                data: "3Bxs".to_string(),
+                stack_height: Some(2),
+            })],
+        }];
+
+        assert_eq!(
+            collect_inner_instructions(&inner, &account_keys),
+            vec![InnerInstruction {
+                instruction_index: 0,
+                program_id: "Vote111".to_string(),
+                stack_height: Some(2),
+                data: Some("3Bxs".to_string()),
+            }],
+        );
+    }
+}
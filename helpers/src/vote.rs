@@ -0,0 +1,459 @@
+//! Vote account discovery via `getProgramAccounts`.
+
+use {
+    serde::Serialize,
+    solana_account::Account,
+    solana_client::{
+        rpc_client::RpcClient,
+        rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, UiAccountEncoding},
+        rpc_filter::{Memcmp, RpcFilterType},
+    },
+    solana_commitment_config::CommitmentConfig,
+    solana_pubkey::Pubkey,
+    solana_vote_interface::state::VoteStateVersions,
+    std::{
+        fmt, fs, io,
+        path::{Path, PathBuf},
+        str::FromStr,
+    },
+};
+
+/// Offset of the `Option` tag of `bls_pubkey_compressed` in a serialized
+/// `VoteStateVersions::V4`: the variant, four pubkeys, two commissions and
+/// the pending delegator rewards.
+const V4_BLS_PUBKEY_OFFSET: usize = 4 + 32 * 4 + 2 + 2 + 8;
+
+/// Offsets of `node_pubkey` and `authorized_withdrawer`, which follow the
+/// variant in every serialized `VoteStateVersions`.
+const NODE_PUBKEY_OFFSET: usize = 4;
+const AUTHORIZED_WITHDRAWER_OFFSET: usize = 4 + 32;
+
+/// A serialized `VoteStateVersions` variant, selected by its leading `u32`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteStateVersion {
+    V1_14_11,
+    V3,
+    #[default]
+    V4,
+}
+
+impl VoteStateVersion {
+    pub fn discriminant(&self) -> u32 {
+        match self {
+            Self::V1_14_11 => 1,
+            Self::V3 => 2,
+            Self::V4 => 3,
+        }
+    }
+}
+
+impl FromStr for VoteStateVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "v1_14_11" => Ok(Self::V1_14_11),
+            "v3" => Ok(Self::V3),
+            "v4" => Ok(Self::V4),
+            other => Err(format!(
+                "unknown vote state version: {other} (expected v1_14_11, v3, or v4)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for VoteStateVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::V1_14_11 => write!(f, "v1_14_11"),
+            Self::V3 => write!(f, "v3"),
+            Self::V4 => write!(f, "v4"),
+        }
+    }
+}
+
+/// Which vote accounts to fetch. The version, node, authorized withdrawer and
+/// BLS key presence are matched on-chain with memcmp filters; the commission
+/// range is applied client-side, since its encoding differs between versions.
+#[derive(Clone, Debug, Default)]
+pub struct VoteAccountFilter {
+    pub version: VoteStateVersion,
+    /// Only v4 accounts that do (`Some(true)`) or don't (`Some(false)`) have
+    /// a BLS public key set.
+    pub has_bls_key: Option<bool>,
+    pub node_pubkey: Option<Pubkey>,
+    pub authorized_withdrawer: Option<Pubkey>,
+    pub min_commission_bps: Option<u16>,
+    pub max_commission_bps: Option<u16>,
+}
+
+impl VoteAccountFilter {
+    pub fn rpc_filters(&self) -> Result<Vec<RpcFilterType>, String> {
+        let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            0,
+            self.version.discriminant().to_le_bytes().to_vec(),
+        ))];
+        if let Some(has_bls_key) = self.has_bls_key {
+            if self.version != VoteStateVersion::V4 {
+                return Err(format!(
+                    "{} vote accounts have no BLS public key",
+                    self.version
+                ));
+            }
+            filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                V4_BLS_PUBKEY_OFFSET,
+                vec![has_bls_key as u8],
+            )));
+        }
+        for (offset, pubkey) in [
+            (NODE_PUBKEY_OFFSET, self.node_pubkey),
+            (AUTHORIZED_WITHDRAWER_OFFSET, self.authorized_withdrawer),
+        ] {
+            if let Some(pubkey) = pubkey {
+                filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    offset,
+                    pubkey.to_bytes().to_vec(),
+                )));
+            }
+        }
+        Ok(filters)
+    }
+
+    pub fn matches(&self, summary: &VoteAccountSummary) -> bool {
+        self.min_commission_bps
+            .is_none_or(|min| summary.commission_bps >= min)
+            && self
+                .max_commission_bps
+                .is_none_or(|max| summary.commission_bps <= max)
+    }
+}
+
+/// The fields of a vote account worth listing.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct VoteAccountSummary {
+    pub pubkey: String,
+    pub version: VoteStateVersion,
+    pub node_pubkey: String,
+    pub authorized_withdrawer: String,
+    /// Inflation rewards commission in basis points. Pre-v4 percentages are
+    /// scaled up so all versions compare on the same footing.
+    pub commission_bps: u16,
+    /// Whether a BLS public key is set. Always `None` before v4.
+    pub has_bls_key: Option<bool>,
+    pub lamports: u64,
+}
+
+impl VoteAccountSummary {
+    pub fn from_account(pubkey: &Pubkey, account: &Account) -> Option<Self> {
+        let (version, node_pubkey, authorized_withdrawer, commission_bps, has_bls_key) =
+            match VoteStateVersions::deserialize(&account.data).ok()? {
+                VoteStateVersions::V1_14_11(state) => (
+                    VoteStateVersion::V1_14_11,
+                    state.node_pubkey,
+                    state.authorized_withdrawer,
+                    u16::from(state.commission) * 100,
+                    None,
+                ),
+                VoteStateVersions::V3(state) => (
+                    VoteStateVersion::V3,
+                    state.node_pubkey,
+                    state.authorized_withdrawer,
+                    u16::from(state.commission) * 100,
+                    None,
+                ),
+                VoteStateVersions::V4(state) => (
+                    VoteStateVersion::V4,
+                    state.node_pubkey,
+                    state.authorized_withdrawer,
+                    state.inflation_rewards_commission_bps,
+                    Some(state.bls_pubkey_compressed.is_some()),
+                ),
+                VoteStateVersions::Uninitialized => return None,
+            };
+        Some(Self {
+            pubkey: pubkey.to_string(),
+            version,
+            node_pubkey: node_pubkey.to_string(),
+            authorized_withdrawer: authorized_withdrawer.to_string(),
+            commission_bps,
+            has_bls_key,
+            lamports: account.lamports,
+        })
+    }
+}
+
+/// Fetch every vote account matching `filter`, sorted by pubkey.
+pub fn fetch_vote_accounts(
+    client: &RpcClient,
+    filter: &VoteAccountFilter,
+) -> Result<Vec<VoteAccountSummary>, String> {
+    let accounts = client
+        .get_program_ui_accounts_with_config(
+            &solana_sdk_ids::vote::ID,
+            RpcProgramAccountsConfig {
+                filters: Some(filter.rpc_filters()?),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    ..RpcAccountInfoConfig::default()
+                },
+                ..RpcProgramAccountsConfig::default()
+            },
+        )
+        .map_err(|err| format!("failed to fetch vote accounts: {err}"))?;
+
+    let mut summaries: Vec<VoteAccountSummary> = accounts
+        .iter()
+        .filter_map(|(pubkey, account)| {
+            VoteAccountSummary::from_account(pubkey, &account.decode()?)
+        })
+        .filter(|summary| filter.matches(summary))
+        .collect();
+    summaries.sort_by(|a, b| a.pubkey.cmp(&b.pubkey));
+    Ok(summaries)
+}
+
+/// File formats for a vote account listing, selected with
+/// `--format <text|csv|json>`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListingFormat {
+    /// One line per account, pubkey first, so `cut -d' ' -f1` yields the
+    /// bare address list.
+    #[default]
+    Text,
+    Csv,
+    Json,
+}
+
+impl ListingFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+
+    /// Where `fetch-vote-accounts` saves `version` accounts fetched from
+    /// `network` (see `crate::network_name`), and where `0185 sweep` reads
+    /// them from, e.g. `scripts/out/vote_v4_accounts_testnet.txt`.
+    pub fn path(&self, version: VoteStateVersion, network: &str) -> PathBuf {
+        // An RPC URL is kept readable but made safe as a file name.
+        let network = network
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        crate::scripts_out_dir().join(format!(
+            "vote_{version}_accounts_{network}.{}",
+            self.extension()
+        ))
+    }
+
+    pub fn render(&self, summaries: &[VoteAccountSummary]) -> String {
+        let has_bls_key = |summary: &VoteAccountSummary| match summary.has_bls_key {
+            Some(true) => "yes",
+            Some(false) => "no",
+            None => "-",
+        };
+        match self {
+            Self::Text => summaries
+                .iter()
+                .map(|summary| {
+                    format!(
+                        "{:<44} {:<8} {:>5} {:<3} {}\n",
+                        summary.pubkey,
+                        summary.version,
+                        summary.commission_bps,
+                        has_bls_key(summary),
+                        summary.node_pubkey,
+                    )
+                })
+                .collect(),
+            Self::Csv => {
+                let mut csv = String::from(
+                    "pubkey,version,node_pubkey,authorized_withdrawer,commission_bps,has_bls_key,\
+                     lamports\n",
+                );
+                for summary in summaries {
+                    csv.push_str(&format!(
+                        "{},{},{},{},{},{},{}\n",
+                        summary.pubkey,
+                        summary.version,
+                        summary.node_pubkey,
+                        summary.authorized_withdrawer,
+                        summary.commission_bps,
+                        has_bls_key(summary),
+                        summary.lamports,
+                    ));
+                }
+                csv
+            }
+            Self::Json => {
+                let mut json = serde_json::to_string_pretty(summaries)
+                    .expect("failed to serialize vote accounts");
+                json.push('\n');
+                json
+            }
+        }
+    }
+
+    pub fn write(&self, path: &Path, summaries: &[VoteAccountSummary]) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.render(summaries))
+    }
+}
+
+impl FromStr for ListingFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "unknown format: {other} (expected text, csv, or json)"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_vote_interface::state::{VoteInit, VoteStateV4},
+    };
+
+    fn v4_account(bls_pubkey_compressed: Option<[u8; 48]>, commission: u8) -> Account {
+        let vote_state = VoteStateV4 {
+            bls_pubkey_compressed,
+            ..VoteStateV4::new_with_defaults(
+                &Pubkey::new_unique(),
+                &VoteInit {
+                    node_pubkey: Pubkey::new_unique(),
+                    authorized_voter: Pubkey::new_unique(),
+                    authorized_withdrawer: Pubkey::new_unique(),
+                    commission,
+                },
+                &Default::default(),
+            )
+        };
+        let mut data = vec![0; VoteStateV4::size_of()];
+        VoteStateV4::serialize(&VoteStateVersions::new_v4(vote_state), &mut data).unwrap();
+        Account {
+            lamports: 1,
+            data,
+            owner: solana_sdk_ids::vote::ID,
+            ..Account::default()
+        }
+    }
+
+    fn memcmp_matches(filter: &RpcFilterType, data: &[u8]) -> bool {
+        let RpcFilterType::Memcmp(memcmp) = filter else {
+            panic!("expected memcmp filter");
+        };
+        memcmp.bytes_match(data)
+    }
+
+    #[test]
+    fn memcmp_filters_match_serialized_v4() {
+        let with_bls = v4_account(Some([7; 48]), 0);
+        let without_bls = v4_account(None, 0);
+
+        let filter = VoteAccountFilter {
+            has_bls_key: Some(true),
+            ..VoteAccountFilter::default()
+        };
+        let filters = filter.rpc_filters().unwrap();
+        assert!(filters.iter().all(|f| memcmp_matches(f, &with_bls.data)));
+        assert!(!filters.iter().all(|f| memcmp_matches(f, &without_bls.data)));
+
+        let filter = VoteAccountFilter {
+            version: VoteStateVersion::V3,
+            ..VoteAccountFilter::default()
+        };
+        let filters = filter.rpc_filters().unwrap();
+        assert!(!memcmp_matches(&filters[0], &with_bls.data));
+    }
+
+    #[test]
+    fn memcmp_filters_match_node_and_withdrawer() {
+        let account = v4_account(None, 0);
+        let VoteStateVersions::V4(state) = VoteStateVersions::deserialize(&account.data).unwrap()
+        else {
+            panic!("expected v4 vote state");
+        };
+
+        let filter = |node_pubkey, authorized_withdrawer| VoteAccountFilter {
+            node_pubkey,
+            authorized_withdrawer,
+            ..VoteAccountFilter::default()
+        };
+        let matches = |filter: VoteAccountFilter| {
+            let filters = filter.rpc_filters().unwrap();
+            filters.iter().all(|f| memcmp_matches(f, &account.data))
+        };
+        assert!(matches(filter(
+            Some(state.node_pubkey),
+            Some(state.authorized_withdrawer)
+        )));
+        assert!(!matches(filter(Some(state.authorized_withdrawer), None)));
+        assert!(!matches(filter(None, Some(Pubkey::new_unique()))));
+    }
+
+    #[test]
+    fn reject_bls_filter_before_v4() {
+        let filter = VoteAccountFilter {
+            version: VoteStateVersion::V1_14_11,
+            has_bls_key: Some(false),
+            ..VoteAccountFilter::default()
+        };
+        assert!(filter.rpc_filters().is_err());
+    }
+
+    #[test]
+    fn summarize_and_filter_commission() {
+        let pubkey = Pubkey::new_unique();
+        let summary = VoteAccountSummary::from_account(&pubkey, &v4_account(None, 5)).unwrap();
+        assert_eq!(summary.version, VoteStateVersion::V4);
+        assert_eq!(summary.commission_bps, 500);
+        assert_eq!(summary.has_bls_key, Some(false));
+
+        let filter = |min, max| VoteAccountFilter {
+            min_commission_bps: min,
+            max_commission_bps: max,
+            ..VoteAccountFilter::default()
+        };
+        assert!(filter(None, None).matches(&summary));
+        assert!(filter(Some(500), Some(500)).matches(&summary));
+        assert!(!filter(Some(501), None).matches(&summary));
+        assert!(!filter(None, Some(499)).matches(&summary));
+
+        let csv = ListingFormat::Csv.render(&[summary]);
+        assert!(csv.lines().nth(1).unwrap().starts_with(&pubkey.to_string()));
+    }
+
+    #[test]
+    fn listing_path_by_network() {
+        let file_name = |format: ListingFormat, version, network| {
+            let path = format.path(version, network);
+            path.file_name().unwrap().to_str().unwrap().to_string()
+        };
+        assert_eq!(
+            file_name(ListingFormat::Text, VoteStateVersion::V4, "testnet"),
+            "vote_v4_accounts_testnet.txt"
+        );
+        assert_eq!(
+            file_name(
+                ListingFormat::Json,
+                VoteStateVersion::V3,
+                "https://rpc.example.com:8899"
+            ),
+            "vote_v3_accounts_https___rpc_example_com_8899.json"
+        );
+    }
+}
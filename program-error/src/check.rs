@@ -0,0 +1,59 @@
+//! Account checks run by the test programs before any CPI, so misuse of a
+//! test program surfaces as a `TestProgramError` rather than as a failure
+//! inside the program under test.
+
+use {crate::TestProgramError, solana_account_info::AccountInfo, solana_pubkey::Pubkey};
+
+/// Split off exactly `N` accounts, ignoring any extras.
+pub fn accounts<'a, 'b, const N: usize>(
+    accounts: &'a [AccountInfo<'b>],
+) -> Result<&'a [AccountInfo<'b>; N], TestProgramError> {
+    accounts
+        .get(..N)
+        .and_then(|accounts| accounts.try_into().ok())
+        .ok_or(TestProgramError::NotEnoughAccounts)
+}
+
+pub fn signer(account: &AccountInfo) -> Result<(), TestProgramError> {
+    if !account.is_signer {
+        return Err(TestProgramError::AccountNotSigner);
+    }
+    Ok(())
+}
+
+pub fn writable(account: &AccountInfo) -> Result<(), TestProgramError> {
+    if !account.is_writable {
+        return Err(TestProgramError::AccountNotWritable);
+    }
+    Ok(())
+}
+
+pub fn owner(account: &AccountInfo, owner: &Pubkey) -> Result<(), TestProgramError> {
+    if account.owner != owner {
+        return Err(TestProgramError::WrongAccountOwner);
+    }
+    Ok(())
+}
+
+/// The account must be owned by the system program and hold no data.
+pub fn uninitialized(account: &AccountInfo) -> Result<(), TestProgramError> {
+    owner(account, &solana_sdk_ids::system_program::ID)?;
+    if !account.data_is_empty() {
+        return Err(TestProgramError::AccountAlreadyInitialized);
+    }
+    Ok(())
+}
+
+pub fn sysvar(account: &AccountInfo, id: &Pubkey) -> Result<(), TestProgramError> {
+    if account.key != id {
+        return Err(TestProgramError::InvalidSysvar);
+    }
+    Ok(())
+}
+
+pub fn program(account: &AccountInfo, id: &Pubkey) -> Result<(), TestProgramError> {
+    if account.key != id {
+        return Err(TestProgramError::InvalidProgramAccount);
+    }
+    Ok(())
+}
@@ -0,0 +1,83 @@
+//! Errors shared by the SIMD test programs.
+
+pub mod check;
+
+use solana_program_error::ProgramError;
+
+/// Errors returned by the test programs as `ProgramError::Custom(code)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum TestProgramError {
+    /// The instruction data was empty.
+    EmptyInstructionData = 0,
+    /// The instruction tag did not match any known instruction.
+    UnknownInstruction = 1,
+    /// The instruction data was too short for the instruction's payload.
+    TruncatedInstructionData = 2,
+    /// The instruction was given fewer accounts than it expects.
+    NotEnoughAccounts = 3,
+    /// An account is not owned by the expected program.
+    WrongAccountOwner = 4,
+    /// The vote account does not hold a v4 vote state.
+    NotV4VoteState = 5,
+    /// The v4 vote state has no BLS public key set.
+    MissingBlsKey = 6,
+    /// An account that must sign the transaction did not.
+    AccountNotSigner = 7,
+    /// An account that must be writable was passed as read-only.
+    AccountNotWritable = 8,
+    /// A sysvar account did not have the expected sysvar ID.
+    InvalidSysvar = 9,
+    /// A program account did not have the expected program ID.
+    InvalidProgramAccount = 10,
+    /// The identity account does not match the program's identity PDA.
+    InvalidIdentityPda = 11,
+    /// An account expected to be uninitialized already holds data.
+    AccountAlreadyInitialized = 12,
+    /// A commission exceeded 10,000 basis points (100%).
+    CommissionOutOfRange = 13,
+}
+
+impl TryFrom<u32> for TestProgramError {
+    type Error = u32;
+
+    fn try_from(code: u32) -> Result<Self, Self::Error> {
+        match code {
+            0 => Ok(Self::EmptyInstructionData),
+            1 => Ok(Self::UnknownInstruction),
+            2 => Ok(Self::TruncatedInstructionData),
+            3 => Ok(Self::NotEnoughAccounts),
+            4 => Ok(Self::WrongAccountOwner),
+            5 => Ok(Self::NotV4VoteState),
+            6 => Ok(Self::MissingBlsKey),
+            7 => Ok(Self::AccountNotSigner),
+            8 => Ok(Self::AccountNotWritable),
+            9 => Ok(Self::InvalidSysvar),
+            10 => Ok(Self::InvalidProgramAccount),
+            11 => Ok(Self::InvalidIdentityPda),
+            12 => Ok(Self::AccountAlreadyInitialized),
+            13 => Ok(Self::CommissionOutOfRange),
+            _ => Err(code),
+        }
+    }
+}
+
+impl From<TestProgramError> for ProgramError {
+    fn from(err: TestProgramError) -> Self {
+        ProgramError::Custom(err as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_roundtrip() {
+        for code in 0..=13 {
+            let err = TestProgramError::try_from(code).unwrap();
+            assert_eq!(ProgramError::from(err), ProgramError::Custom(code));
+        }
+        assert_eq!(TestProgramError::try_from(14), Err(14));
+    }
+}
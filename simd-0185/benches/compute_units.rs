@@ -0,0 +1,61 @@
+//! Compute units for each SIMD-0185 instruction variant, with
+//! `vote_state_v4` on and off. Run with `make bench-simd-0185`.
+
+use {
+    agave_feature_set::vote_state_v4,
+    cu_bench::CuBench,
+    mollusk_svm::{
+        program::{create_keyed_account_for_builtin_program, keyed_account_for_system_program},
+        Mollusk,
+    },
+    simd_0185_interface::{get_identity_pda, ProgramInstruction},
+    solana_account::Account,
+    solana_pubkey::Pubkey,
+    solana_vote_interface::state::VoteStateV4,
+    vote_fixture::VoteAccountFixture,
+};
+
+fn main() {
+    let program_id = Pubkey::new_unique();
+    let mollusk = || Mollusk::new(&program_id, "simd_0185");
+
+    let mut bench = CuBench::new("simd-0185", &[("vote_state_v4", vote_state_v4::id())]);
+
+    // Create: system and vote program CPIs.
+    bench.bench("create", mollusk, |mollusk| {
+        let payer = Pubkey::new_unique();
+        let vote_account = Pubkey::new_unique();
+        let (identity_pda, _) = get_identity_pda(&program_id);
+        let create_ix = ProgramInstruction::create(
+            &program_id,
+            &payer,
+            &vote_account,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            10,
+        );
+        let lamports = mollusk.sysvars.rent.minimum_balance(VoteStateV4::size_of());
+        let accounts = [
+            (
+                payer,
+                Account::new(lamports * 2, 0, &solana_sdk_ids::system_program::ID),
+            ),
+            (vote_account, Account::default()),
+            (identity_pda, Account::default()),
+            mollusk.sysvars.keyed_account_for_rent_sysvar(),
+            mollusk.sysvars.keyed_account_for_clock_sysvar(),
+            keyed_account_for_system_program(),
+            create_keyed_account_for_builtin_program(&solana_sdk_ids::vote::ID, "vote_program"),
+        ];
+        mollusk.process_instruction(&create_ix, &accounts)
+    });
+
+    // View: deserialize and log an existing v4 account.
+    let (vote_account, account) = VoteAccountFixture::v4().with_commission_bps(1_000).build();
+    let view_ix = ProgramInstruction::view(&program_id, &vote_account);
+    bench.bench("view", mollusk, |mollusk| {
+        mollusk.process_instruction(&view_ix, &[(vote_account, account.clone())])
+    });
+
+    bench.finish();
+}
@@ -1,18 +1,24 @@
 //! Interface for the SIMD-0185 test program.
 
 use {
+    program_error::TestProgramError,
     solana_instruction::{AccountMeta, Instruction},
+    solana_program_error::ProgramError,
     solana_pubkey::Pubkey,
     solana_vote_interface::{instruction::VoteInstruction, state::VoteInit},
 };
 
+/// The largest commission the program accepts, 100%.
+pub const MAX_COMMISSION_BPS: u16 = 10_000;
+
+#[derive(Debug, PartialEq, Eq)]
 pub enum ProgramInstruction {
     /// Create a v4 vote account via CPI.
     ///
     /// Accounts expected by this instruction:
     ///
     /// 0. `[ws]` Payer
-    /// 1. `[w]` Uninitialized vote account
+    /// 1. `[ws]` Uninitialized vote account
     /// 2. `[ ]` Identity PDA (below)
     /// 3. `[ ]` Rent sysvar
     /// 4. `[ ]` Clock sysvar
@@ -30,27 +36,108 @@ pub enum ProgramInstruction {
     ///
     /// 0. `[ ]` Initialized vote account
     View,
+
+    /// Set a v4 vote account's inflation rewards commission, in basis
+    /// points, via CPI to the Vote Program's `UpdateCommissionBps`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    /// 0. `[w]` Vote account
+    /// 1. `[s]` Authorized withdrawer (the program signs if this is the
+    ///    identity PDA)
+    /// 2. `[ ]` Vote program
+    SetInflationRewardsCommission { commission_bps: u16 },
+
+    /// Set a v4 vote account's block revenue commission, in basis points,
+    /// via CPI to the Vote Program's `UpdateCommissionBps`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    /// 0. `[w]` Vote account
+    /// 1. `[s]` Authorized withdrawer (the program signs if this is the
+    ///    identity PDA)
+    /// 2. `[ ]` Vote program
+    SetBlockRevenueCommission { commission_bps: u16 },
+
+    /// Set a v4 vote account's inflation rewards collector via CPI to the
+    /// Vote Program's `UpdateCommissionCollector`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    /// 0. `[w]` Vote account
+    /// 1. `[w]` New collector: the vote account, or a rent-exempt system
+    ///    account
+    /// 2. `[s]` Authorized withdrawer (the program signs if this is the
+    ///    identity PDA)
+    /// 3. `[ ]` Vote program
+    SetInflationRewardsCollector,
+
+    /// Set a v4 vote account's block revenue collector via CPI to the Vote
+    /// Program's `UpdateCommissionCollector`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    /// 0. `[w]` Vote account
+    /// 1. `[w]` New collector: the vote account, or a rent-exempt system
+    ///    account
+    /// 2. `[s]` Authorized withdrawer (the program signs if this is the
+    ///    identity PDA)
+    /// 3. `[ ]` Vote program
+    SetBlockRevenueCollector,
+
+    /// Withdraw all of a vote account's lamports via CPI to the Vote
+    /// Program's `Withdraw`, closing it.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    /// 0. `[w]` Vote account
+    /// 1. `[w]` Recipient
+    /// 2. `[s]` Authorized withdrawer (the program signs if this is the
+    ///    identity PDA)
+    /// 3. `[ ]` Vote program
+    Withdraw,
 }
 
 impl ProgramInstruction {
     const CREATE: u8 = 0;
     const VIEW: u8 = 1;
+    const SET_INFLATION_REWARDS_COMMISSION: u8 = 2;
+    const SET_BLOCK_REVENUE_COMMISSION: u8 = 3;
+    const SET_INFLATION_REWARDS_COLLECTOR: u8 = 4;
+    const SET_BLOCK_REVENUE_COLLECTOR: u8 = 5;
+    const WITHDRAW: u8 = 6;
 
-    pub fn decode(input: &[u8]) -> Self {
-        match input.first() {
-            Some(&Self::CREATE) => {
-                let authorized_voter = Pubkey::new_from_array(input[1..33].try_into().unwrap());
+    pub fn decode(input: &[u8]) -> Result<Self, ProgramError> {
+        let (&tag, rest) = input
+            .split_first()
+            .ok_or(TestProgramError::EmptyInstructionData)?;
+        match tag {
+            Self::CREATE => {
+                let payload: &[u8; 65] = rest
+                    .get(..65)
+                    .and_then(|payload| payload.try_into().ok())
+                    .ok_or(TestProgramError::TruncatedInstructionData)?;
+                let authorized_voter = Pubkey::new_from_array(payload[..32].try_into().unwrap());
                 let authorized_withdrawer =
-                    Pubkey::new_from_array(input[33..65].try_into().unwrap());
-                let commission = input[65];
-                Self::Create {
+                    Pubkey::new_from_array(payload[32..64].try_into().unwrap());
+                let commission = payload[64];
+                Ok(Self::Create {
                     authorized_voter,
                     authorized_withdrawer,
                     commission,
-                }
+                })
             }
-            Some(&Self::VIEW) => Self::View,
-            _ => panic!("invalid instruction"),
+            Self::VIEW => Ok(Self::View),
+            Self::SET_INFLATION_REWARDS_COMMISSION => Ok(Self::SetInflationRewardsCommission {
+                commission_bps: decode_commission_bps(rest)?,
+            }),
+            Self::SET_BLOCK_REVENUE_COMMISSION => Ok(Self::SetBlockRevenueCommission {
+                commission_bps: decode_commission_bps(rest)?,
+            }),
+            Self::SET_INFLATION_REWARDS_COLLECTOR => Ok(Self::SetInflationRewardsCollector),
+            Self::SET_BLOCK_REVENUE_COLLECTOR => Ok(Self::SetBlockRevenueCollector),
+            Self::WITHDRAW => Ok(Self::Withdraw),
+            _ => Err(TestProgramError::UnknownInstruction.into()),
         }
     }
 
@@ -92,6 +179,141 @@ impl ProgramInstruction {
             data: vec![Self::VIEW],
         }
     }
+
+    pub fn set_inflation_rewards_commission(
+        program_id: &Pubkey,
+        vote_account: &Pubkey,
+        authorized_withdrawer: &Pubkey,
+        commission_bps: u16,
+    ) -> Instruction {
+        Self::set_commission(
+            Self::SET_INFLATION_REWARDS_COMMISSION,
+            program_id,
+            vote_account,
+            authorized_withdrawer,
+            commission_bps,
+        )
+    }
+
+    pub fn set_block_revenue_commission(
+        program_id: &Pubkey,
+        vote_account: &Pubkey,
+        authorized_withdrawer: &Pubkey,
+        commission_bps: u16,
+    ) -> Instruction {
+        Self::set_commission(
+            Self::SET_BLOCK_REVENUE_COMMISSION,
+            program_id,
+            vote_account,
+            authorized_withdrawer,
+            commission_bps,
+        )
+    }
+
+    fn set_commission(
+        tag: u8,
+        program_id: &Pubkey,
+        vote_account: &Pubkey,
+        authorized_withdrawer: &Pubkey,
+        commission_bps: u16,
+    ) -> Instruction {
+        let mut data = Vec::with_capacity(3);
+        data.push(tag);
+        data.extend_from_slice(&commission_bps.to_le_bytes());
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*vote_account, false),
+                withdrawer_meta(program_id, authorized_withdrawer),
+                AccountMeta::new_readonly(solana_sdk_ids::vote::ID, false),
+            ],
+            data,
+        }
+    }
+
+    pub fn set_inflation_rewards_collector(
+        program_id: &Pubkey,
+        vote_account: &Pubkey,
+        new_collector: &Pubkey,
+        authorized_withdrawer: &Pubkey,
+    ) -> Instruction {
+        Self::set_collector(
+            Self::SET_INFLATION_REWARDS_COLLECTOR,
+            program_id,
+            vote_account,
+            new_collector,
+            authorized_withdrawer,
+        )
+    }
+
+    pub fn set_block_revenue_collector(
+        program_id: &Pubkey,
+        vote_account: &Pubkey,
+        new_collector: &Pubkey,
+        authorized_withdrawer: &Pubkey,
+    ) -> Instruction {
+        Self::set_collector(
+            Self::SET_BLOCK_REVENUE_COLLECTOR,
+            program_id,
+            vote_account,
+            new_collector,
+            authorized_withdrawer,
+        )
+    }
+
+    fn set_collector(
+        tag: u8,
+        program_id: &Pubkey,
+        vote_account: &Pubkey,
+        new_collector: &Pubkey,
+        authorized_withdrawer: &Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*vote_account, false),
+                AccountMeta::new(*new_collector, false),
+                withdrawer_meta(program_id, authorized_withdrawer),
+                AccountMeta::new_readonly(solana_sdk_ids::vote::ID, false),
+            ],
+            data: vec![tag],
+        }
+    }
+
+    pub fn withdraw(
+        program_id: &Pubkey,
+        vote_account: &Pubkey,
+        recipient: &Pubkey,
+        authorized_withdrawer: &Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*vote_account, false),
+                AccountMeta::new(*recipient, false),
+                withdrawer_meta(program_id, authorized_withdrawer),
+                AccountMeta::new_readonly(solana_sdk_ids::vote::ID, false),
+            ],
+            data: vec![Self::WITHDRAW],
+        }
+    }
+}
+
+fn decode_commission_bps(rest: &[u8]) -> Result<u16, ProgramError> {
+    rest.get(..2)
+        .map(|bytes| u16::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| TestProgramError::TruncatedInstructionData.into())
+}
+
+/// The authorized withdrawer signs, unless it is the identity PDA, which the
+/// program signs for.
+fn withdrawer_meta(program_id: &Pubkey, authorized_withdrawer: &Pubkey) -> AccountMeta {
+    let (identity_pda, _) = get_identity_pda(program_id);
+    AccountMeta::new_readonly(
+        *authorized_withdrawer,
+        *authorized_withdrawer != identity_pda,
+    )
 }
 
 const PREFIX: &[u8] = b"test_identity";
@@ -123,7 +345,30 @@ pub fn vote_initialize_account(vote_pubkey: &Pubkey, vote_init: &VoteInit) -> In
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use {
+        super::*,
+        proptest::{collection::vec, prelude::*},
+    };
+
+    fn pubkey() -> impl Strategy<Value = Pubkey> {
+        any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+    }
+
+    fn create_data(
+        authorized_voter: &Pubkey,
+        authorized_withdrawer: &Pubkey,
+        commission: u8,
+    ) -> Vec<u8> {
+        ProgramInstruction::create(
+            &Pubkey::default(),
+            &Pubkey::default(),
+            &Pubkey::default(),
+            authorized_voter,
+            authorized_withdrawer,
+            commission,
+        )
+        .data
+    }
 
     #[test]
     fn test_create_roundtrip() {
@@ -143,7 +388,7 @@ mod tests {
             commission,
         );
 
-        match ProgramInstruction::decode(&ix.data) {
+        match ProgramInstruction::decode(&ix.data).unwrap() {
             ProgramInstruction::Create {
                 authorized_voter: decoded_voter,
                 authorized_withdrawer: decoded_withdrawer,
@@ -156,4 +401,195 @@ mod tests {
             _ => panic!("expected Create"),
         }
     }
+
+    #[test]
+    fn test_view_roundtrip() {
+        let ix = ProgramInstruction::view(&Pubkey::new_unique(), &Pubkey::new_unique());
+        assert_eq!(
+            ProgramInstruction::decode(&ix.data),
+            Ok(ProgramInstruction::View)
+        );
+    }
+
+    #[test]
+    fn test_set_commission_roundtrip() {
+        let program_id = Pubkey::new_unique();
+        let vote_account = Pubkey::new_unique();
+        let authorized_withdrawer = Pubkey::new_unique();
+
+        let ix = ProgramInstruction::set_inflation_rewards_commission(
+            &program_id,
+            &vote_account,
+            &authorized_withdrawer,
+            550,
+        );
+        assert_eq!(
+            ProgramInstruction::decode(&ix.data),
+            Ok(ProgramInstruction::SetInflationRewardsCommission {
+                commission_bps: 550
+            })
+        );
+        assert!(ix.accounts[1].is_signer);
+
+        // The program signs for the identity PDA.
+        let (identity_pda, _) = get_identity_pda(&program_id);
+        let ix = ProgramInstruction::set_block_revenue_commission(
+            &program_id,
+            &vote_account,
+            &identity_pda,
+            MAX_COMMISSION_BPS,
+        );
+        assert_eq!(
+            ProgramInstruction::decode(&ix.data),
+            Ok(ProgramInstruction::SetBlockRevenueCommission {
+                commission_bps: MAX_COMMISSION_BPS
+            })
+        );
+        assert!(!ix.accounts[1].is_signer);
+    }
+
+    #[test]
+    fn test_set_collector_roundtrip() {
+        let program_id = Pubkey::new_unique();
+        let vote_account = Pubkey::new_unique();
+        let new_collector = Pubkey::new_unique();
+        let (identity_pda, _) = get_identity_pda(&program_id);
+
+        let ix = ProgramInstruction::set_inflation_rewards_collector(
+            &program_id,
+            &vote_account,
+            &new_collector,
+            &identity_pda,
+        );
+        assert_eq!(
+            ProgramInstruction::decode(&ix.data),
+            Ok(ProgramInstruction::SetInflationRewardsCollector)
+        );
+        assert!(ix.accounts[1].is_writable);
+        assert!(!ix.accounts[2].is_signer);
+
+        let ix = ProgramInstruction::set_block_revenue_collector(
+            &program_id,
+            &vote_account,
+            &new_collector,
+            &identity_pda,
+        );
+        assert_eq!(
+            ProgramInstruction::decode(&ix.data),
+            Ok(ProgramInstruction::SetBlockRevenueCollector)
+        );
+    }
+
+    #[test]
+    fn test_withdraw_roundtrip() {
+        let program_id = Pubkey::new_unique();
+        let ix = ProgramInstruction::withdraw(
+            &program_id,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+        );
+        assert_eq!(
+            ProgramInstruction::decode(&ix.data),
+            Ok(ProgramInstruction::Withdraw)
+        );
+        assert!(ix.accounts[1].is_writable);
+        assert!(ix.accounts[2].is_signer);
+    }
+
+    #[test]
+    fn fail_decode_empty() {
+        assert_eq!(
+            ProgramInstruction::decode(&[]),
+            Err(TestProgramError::EmptyInstructionData.into()),
+        );
+    }
+
+    #[test]
+    fn fail_decode_unknown_tag() {
+        assert_eq!(
+            ProgramInstruction::decode(&[7]),
+            Err(TestProgramError::UnknownInstruction.into()),
+        );
+    }
+
+    #[test]
+    fn fail_decode_truncated() {
+        let ix = ProgramInstruction::create(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            10,
+        );
+        assert_eq!(
+            ProgramInstruction::decode(&ix.data[..ix.data.len() - 1]),
+            Err(TestProgramError::TruncatedInstructionData.into()),
+        );
+        assert_eq!(
+            ProgramInstruction::decode(&ix.data[..1]),
+            Err(TestProgramError::TruncatedInstructionData.into()),
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn decode_arbitrary_bytes(input in vec(any::<u8>(), 0..128)) {
+            match ProgramInstruction::decode(&input) {
+                Ok(ProgramInstruction::Create {
+                    authorized_voter,
+                    authorized_withdrawer,
+                    commission,
+                }) => prop_assert_eq!(
+                    create_data(&authorized_voter, &authorized_withdrawer, commission),
+                    &input[..66]
+                ),
+                Ok(ProgramInstruction::View) => prop_assert_eq!(input[0], 1),
+                Ok(ProgramInstruction::SetInflationRewardsCommission { commission_bps })
+                | Ok(ProgramInstruction::SetBlockRevenueCommission { commission_bps }) => {
+                    prop_assert!(input[0] == 2 || input[0] == 3);
+                    prop_assert_eq!(&commission_bps.to_le_bytes(), &input[1..3]);
+                }
+                Ok(ProgramInstruction::SetInflationRewardsCollector) => prop_assert_eq!(input[0], 4),
+                Ok(ProgramInstruction::SetBlockRevenueCollector) => prop_assert_eq!(input[0], 5),
+                Ok(ProgramInstruction::Withdraw) => prop_assert_eq!(input[0], 6),
+                Err(err) => prop_assert!([
+                    TestProgramError::EmptyInstructionData.into(),
+                    TestProgramError::TruncatedInstructionData.into(),
+                    TestProgramError::UnknownInstruction.into(),
+                ]
+                .contains(&err)),
+            }
+        }
+
+        #[test]
+        fn create_roundtrip(
+            authorized_voter in pubkey(),
+            authorized_withdrawer in pubkey(),
+            commission in any::<u8>(),
+        ) {
+            prop_assert_eq!(
+                ProgramInstruction::decode(&create_data(
+                    &authorized_voter,
+                    &authorized_withdrawer,
+                    commission
+                )),
+                Ok(ProgramInstruction::Create {
+                    authorized_voter,
+                    authorized_withdrawer,
+                    commission,
+                })
+            );
+        }
+
+        #[test]
+        fn reject_unknown_tag(tag in 7..=u8::MAX, rest in vec(any::<u8>(), 0..128)) {
+            let input = [&[tag][..], &rest].concat();
+            prop_assert_eq!(
+                ProgramInstruction::decode(&input),
+                Err(TestProgramError::UnknownInstruction.into())
+            );
+        }
+    }
 }
@@ -1,15 +1,23 @@
 use {
+    program_error::{check, TestProgramError},
     simd_0185_interface::{
         get_identity_pda, get_identity_seeds, vote_initialize_account, ProgramInstruction,
+        MAX_COMMISSION_BPS,
     },
     solana_account_info::AccountInfo,
     solana_cpi::invoke_signed,
+    solana_instruction::Instruction,
     solana_msg::msg,
     solana_program_error::ProgramResult,
     solana_pubkey::Pubkey,
     solana_rent::Rent,
     solana_sysvar::SysvarSerialize,
-    solana_vote_interface::state::{VoteInit, VoteStateV4, VoteStateVersions},
+    solana_vote_interface::{
+        instruction::{
+            update_commission_bps, update_commission_collector, withdraw, CommissionKind,
+        },
+        state::{VoteInit, VoteStateV4, VoteStateVersions},
+    },
 };
 
 solana_program_entrypoint::entrypoint!(process);
@@ -21,13 +29,23 @@ fn process_create(
     authorized_withdrawer: Pubkey,
     commission: u8,
 ) -> ProgramResult {
-    let payer = &accounts[0];
-    let vote_account = &accounts[1];
-    let identity_pda = &accounts[2];
-    let rent_sysvar = &accounts[3];
-    let clock_sysvar = &accounts[4];
+    let [payer, vote_account, identity_pda, rent_sysvar, clock_sysvar, system_program, vote_program] =
+        check::accounts(accounts)?;
+
+    check::signer(payer)?;
+    check::writable(payer)?;
+    check::signer(vote_account)?;
+    check::writable(vote_account)?;
+    check::uninitialized(vote_account)?;
+    let (expected_identity_pda, bump) = get_identity_pda(program_id);
+    if identity_pda.key != &expected_identity_pda {
+        return Err(TestProgramError::InvalidIdentityPda.into());
+    }
+    check::sysvar(rent_sysvar, &solana_sdk_ids::sysvar::rent::ID)?;
+    check::sysvar(clock_sysvar, &solana_sdk_ids::sysvar::clock::ID)?;
+    check::program(system_program, &solana_sdk_ids::system_program::ID)?;
+    check::program(vote_program, &solana_sdk_ids::vote::ID)?;
 
-    let (_, bump) = get_identity_pda(program_id);
     let pda_signer_seeds = &get_identity_seeds(&bump);
 
     let rent = Rent::from_account_info(rent_sysvar)?;
@@ -70,12 +88,12 @@ fn process_create(
 }
 
 fn process_view(accounts: &[AccountInfo]) -> ProgramResult {
-    let vote_account = &accounts[0];
+    let [vote_account] = check::accounts(accounts)?;
+    check::owner(vote_account, &solana_sdk_ids::vote::ID)?;
     let data = vote_account.try_borrow_data()?;
 
-    let vote_state = match VoteStateVersions::deserialize(&data) {
-        Ok(VoteStateVersions::V4(state)) => state,
-        _ => panic!("expected v4 vote state"),
+    let Ok(VoteStateVersions::V4(vote_state)) = VoteStateVersions::deserialize(&data) else {
+        return Err(TestProgramError::NotV4VoteState.into());
     };
 
     msg!("Vote State (v4):");
@@ -107,8 +125,111 @@ fn process_view(accounts: &[AccountInfo]) -> ProgramResult {
     Ok(())
 }
 
+/// Invoke a Vote Program instruction authorized by `authorized_withdrawer`,
+/// with the identity PDA signing in case it is the authorized withdrawer.
+fn invoke_as_withdrawer(
+    program_id: &Pubkey,
+    instruction: &Instruction,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let (_, bump) = get_identity_pda(program_id);
+    invoke_signed(instruction, accounts, &[&get_identity_seeds(&bump)])
+}
+
+fn process_set_commission(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    kind: CommissionKind,
+    commission_bps: u16,
+) -> ProgramResult {
+    let [vote_account, authorized_withdrawer, vote_program] = check::accounts(accounts)?;
+
+    check::writable(vote_account)?;
+    check::owner(vote_account, &solana_sdk_ids::vote::ID)?;
+    check::program(vote_program, &solana_sdk_ids::vote::ID)?;
+    if commission_bps > MAX_COMMISSION_BPS {
+        return Err(TestProgramError::CommissionOutOfRange.into());
+    }
+
+    // CPI to Vote Program: update the commission.
+    let update_ix = update_commission_bps(
+        vote_account.key,
+        authorized_withdrawer.key,
+        kind,
+        commission_bps,
+    );
+    invoke_as_withdrawer(
+        program_id,
+        &update_ix,
+        &[vote_account.clone(), authorized_withdrawer.clone()],
+    )?;
+
+    Ok(())
+}
+
+fn process_set_collector(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    kind: CommissionKind,
+) -> ProgramResult {
+    let [vote_account, new_collector, authorized_withdrawer, vote_program] =
+        check::accounts(accounts)?;
+
+    check::writable(vote_account)?;
+    check::owner(vote_account, &solana_sdk_ids::vote::ID)?;
+    check::program(vote_program, &solana_sdk_ids::vote::ID)?;
+
+    // CPI to Vote Program: update the collector. The Vote Program decides
+    // whether the new collector is allowed.
+    let update_ix = update_commission_collector(
+        vote_account.key,
+        authorized_withdrawer.key,
+        new_collector.key,
+        kind,
+    );
+    invoke_as_withdrawer(
+        program_id,
+        &update_ix,
+        &[
+            vote_account.clone(),
+            new_collector.clone(),
+            authorized_withdrawer.clone(),
+        ],
+    )?;
+
+    Ok(())
+}
+
+fn process_withdraw(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let [vote_account, recipient, authorized_withdrawer, vote_program] = check::accounts(accounts)?;
+
+    check::writable(vote_account)?;
+    check::owner(vote_account, &solana_sdk_ids::vote::ID)?;
+    check::writable(recipient)?;
+    check::program(vote_program, &solana_sdk_ids::vote::ID)?;
+
+    // CPI to Vote Program: withdraw everything, which closes the account.
+    let withdraw_ix = withdraw(
+        vote_account.key,
+        authorized_withdrawer.key,
+        vote_account.lamports(),
+        recipient.key,
+    );
+    invoke_as_withdrawer(
+        program_id,
+        &withdraw_ix,
+        &[
+            vote_account.clone(),
+            recipient.clone(),
+            authorized_withdrawer.clone(),
+        ],
+    )?;
+
+    Ok(())
+}
+
 fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
-    match ProgramInstruction::decode(input) {
+    match ProgramInstruction::decode(input)? {
         ProgramInstruction::Create {
             authorized_voter,
             authorized_withdrawer,
@@ -121,22 +242,49 @@ fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> Progr
             commission,
         ),
         ProgramInstruction::View => process_view(accounts),
+        ProgramInstruction::SetInflationRewardsCommission { commission_bps } => {
+            process_set_commission(
+                program_id,
+                accounts,
+                CommissionKind::InflationRewards,
+                commission_bps,
+            )
+        }
+        ProgramInstruction::SetBlockRevenueCommission { commission_bps } => process_set_commission(
+            program_id,
+            accounts,
+            CommissionKind::BlockRevenue,
+            commission_bps,
+        ),
+        ProgramInstruction::SetInflationRewardsCollector => {
+            process_set_collector(program_id, accounts, CommissionKind::InflationRewards)
+        }
+        ProgramInstruction::SetBlockRevenueCollector => {
+            process_set_collector(program_id, accounts, CommissionKind::BlockRevenue)
+        }
+        ProgramInstruction::Withdraw => process_withdraw(program_id, accounts),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use {
+        agave_feature_set::{bls_pubkey_management_in_vote_account, vote_state_v4},
+        feature_matrix::FeatureMatrix,
         mollusk_svm::{
             program::{create_keyed_account_for_builtin_program, keyed_account_for_system_program},
-            result::Check,
+            result::{Check, ProgramResult},
             Mollusk,
         },
-        simd_0185_interface::{get_identity_pda, ProgramInstruction},
+        program_error::TestProgramError,
+        simd_0185_interface::{get_identity_pda, ProgramInstruction, MAX_COMMISSION_BPS},
         solana_account::Account,
         solana_instruction::{error::InstructionError, Instruction},
         solana_pubkey::Pubkey,
-        solana_vote_interface::state::{VoteStateV4, VoteStateVersions},
+        solana_vote_interface::state::{
+            VoteStateV4, VoteStateVersions, BLS_PUBLIC_KEY_COMPRESSED_SIZE,
+        },
+        vote_fixture::VoteAccountFixture,
     };
 
     fn setup(
@@ -226,16 +374,684 @@ mod tests {
             mollusk.process_and_validate_instruction(&create_ix, &accounts, &[Check::success()]);
 
         // Run view with the resulting vote account — should fail because
-        // the program panics on non-v4 state.
+        // the state is not v4.
         let mut view_accounts = accounts.clone();
         view_accounts[1].1 = result.get_account(&vote_account).unwrap().clone();
 
         mollusk.process_and_validate_instruction(
             &view_ix,
             &view_accounts,
+            &[Check::err(TestProgramError::NotV4VoteState.into())],
+        );
+    }
+
+    #[test]
+    fn feature_matrix_create_and_view() {
+        let program_id = Pubkey::new_unique();
+
+        // Only `vote_state_v4` decides the version the vote program creates;
+        // the BLS feature must not change the outcome.
+        FeatureMatrix::new(&[
+            ("vote_state_v4", vote_state_v4::id()),
+            (
+                "bls_pubkey_management",
+                bls_pubkey_management_in_vote_account::id(),
+            ),
+        ])
+        .run(
+            || Mollusk::new(&program_id, "simd_0185"),
+            |mollusk, _| {
+                let (create_ix, view_ix, accounts) = setup(&program_id, mollusk);
+                mollusk.process_instruction_chain(&[create_ix, view_ix], &accounts)
+            },
+            |combination| {
+                if combination.is_active(&vote_state_v4::id()) {
+                    ProgramResult::Success
+                } else {
+                    ProgramResult::Failure(TestProgramError::NotV4VoteState.into())
+                }
+            },
+        )
+        .assert_passed();
+    }
+
+    #[test]
+    fn test_view_fixture() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+
+        let (vote_account, account) = VoteAccountFixture::v4()
+            .with_commission_bps(550)
+            .with_bls_key([4; BLS_PUBLIC_KEY_COMPRESSED_SIZE])
+            .build();
+        let view_ix = ProgramInstruction::view(&program_id, &vote_account);
+
+        mollusk.process_and_validate_instruction(
+            &view_ix,
+            &[(vote_account, account)],
+            &[Check::success()],
+        );
+    }
+
+    #[test]
+    fn fail_view_pre_v4_fixtures() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+
+        for fixture in [VoteAccountFixture::v1_14_11(), VoteAccountFixture::v3()] {
+            let (vote_account, account) = fixture.build();
+            let view_ix = ProgramInstruction::view(&program_id, &vote_account);
+
+            mollusk.process_and_validate_instruction(
+                &view_ix,
+                &[(vote_account, account)],
+                &[Check::err(TestProgramError::NotV4VoteState.into())],
+            );
+        }
+    }
+
+    #[test]
+    fn fail_view_not_enough_accounts() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+
+        let mut view_ix = ProgramInstruction::view(&program_id, &Pubkey::new_unique());
+        view_ix.accounts.clear();
+
+        mollusk.process_and_validate_instruction(
+            &view_ix,
+            &[],
+            &[Check::err(TestProgramError::NotEnoughAccounts.into())],
+        );
+    }
+
+    #[test]
+    fn fail_view_wrong_owner() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+
+        let vote_account = Pubkey::new_unique();
+        let view_ix = ProgramInstruction::view(&program_id, &vote_account);
+
+        mollusk.process_and_validate_instruction(
+            &view_ix,
+            &[(
+                vote_account,
+                Account::new(
+                    1,
+                    VoteStateV4::size_of(),
+                    &solana_sdk_ids::system_program::ID,
+                ),
+            )],
+            &[Check::err(TestProgramError::WrongAccountOwner.into())],
+        );
+    }
+
+    /// Run `create` after `tamper` has broken one of its account
+    /// constraints, and expect it to fail with `err` before any CPI.
+    fn assert_create_err(
+        tamper: impl FnOnce(&mut Instruction, &mut Vec<(Pubkey, Account)>),
+        err: TestProgramError,
+    ) {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+        let (mut create_ix, _, mut accounts) = setup(&program_id, &mollusk);
+        tamper(&mut create_ix, &mut accounts);
+        mollusk.process_and_validate_instruction(&create_ix, &accounts, &[Check::err(err.into())]);
+    }
+
+    /// Swap the account at `index` for an unrelated one.
+    fn replace_account(index: usize) -> impl FnOnce(&mut Instruction, &mut Vec<(Pubkey, Account)>) {
+        move |ix, accounts| {
+            let key = Pubkey::new_unique();
+            ix.accounts[index].pubkey = key;
+            accounts[index] = (key, Account::default());
+        }
+    }
+
+    #[test]
+    fn fail_create_not_enough_accounts() {
+        assert_create_err(
+            |ix, accounts| {
+                ix.accounts.pop();
+                accounts.pop();
+            },
+            TestProgramError::NotEnoughAccounts,
+        );
+    }
+
+    #[test]
+    fn fail_create_payer_not_signer() {
+        assert_create_err(
+            |ix, _| ix.accounts[0].is_signer = false,
+            TestProgramError::AccountNotSigner,
+        );
+    }
+
+    #[test]
+    fn fail_create_payer_not_writable() {
+        assert_create_err(
+            |ix, _| ix.accounts[0].is_writable = false,
+            TestProgramError::AccountNotWritable,
+        );
+    }
+
+    #[test]
+    fn fail_create_vote_account_not_signer() {
+        assert_create_err(
+            |ix, _| ix.accounts[1].is_signer = false,
+            TestProgramError::AccountNotSigner,
+        );
+    }
+
+    #[test]
+    fn fail_create_vote_account_not_writable() {
+        assert_create_err(
+            |ix, _| ix.accounts[1].is_writable = false,
+            TestProgramError::AccountNotWritable,
+        );
+    }
+
+    #[test]
+    fn fail_create_vote_account_wrong_owner() {
+        assert_create_err(
+            |_, accounts| accounts[1].1.owner = solana_sdk_ids::vote::ID,
+            TestProgramError::WrongAccountOwner,
+        );
+    }
+
+    #[test]
+    fn fail_create_vote_account_already_initialized() {
+        assert_create_err(
+            |_, accounts| {
+                accounts[1].1 = Account::new(1, 8, &solana_sdk_ids::system_program::ID);
+            },
+            TestProgramError::AccountAlreadyInitialized,
+        );
+    }
+
+    #[test]
+    fn fail_create_wrong_identity_pda() {
+        assert_create_err(replace_account(2), TestProgramError::InvalidIdentityPda);
+    }
+
+    #[test]
+    fn fail_create_wrong_rent_sysvar() {
+        assert_create_err(replace_account(3), TestProgramError::InvalidSysvar);
+    }
+
+    #[test]
+    fn fail_create_wrong_clock_sysvar() {
+        assert_create_err(replace_account(4), TestProgramError::InvalidSysvar);
+    }
+
+    #[test]
+    fn fail_create_wrong_system_program() {
+        assert_create_err(replace_account(5), TestProgramError::InvalidProgramAccount);
+    }
+
+    #[test]
+    fn fail_create_wrong_vote_program() {
+        assert_create_err(replace_account(6), TestProgramError::InvalidProgramAccount);
+    }
+
+    fn v4_state(account: &Account) -> Box<VoteStateV4> {
+        match VoteStateVersions::deserialize(&account.data) {
+            Ok(VoteStateVersions::V4(state)) => state,
+            _ => panic!("expected v4 vote state"),
+        }
+    }
+
+    /// A v4 vote account with `authorized_withdrawer`, and the accounts a
+    /// `Set*Commission` instruction for it needs.
+    fn setup_commission(authorized_withdrawer: Pubkey) -> (Pubkey, Vec<(Pubkey, Account)>) {
+        let (vote_account, account) = VoteAccountFixture::v4()
+            .with_withdrawer(authorized_withdrawer)
+            .with_commission_bps(1_000)
+            .build();
+        let accounts = vec![
+            (vote_account, account),
+            (authorized_withdrawer, Account::default()),
+            create_keyed_account_for_builtin_program(&solana_sdk_ids::vote::ID, "vote_program"),
+        ];
+        (vote_account, accounts)
+    }
+
+    #[test]
+    fn test_set_inflation_rewards_commission() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+        let (identity_pda, _) = get_identity_pda(&program_id);
+        let (vote_account, accounts) = setup_commission(identity_pda);
+
+        let ix = ProgramInstruction::set_inflation_rewards_commission(
+            &program_id,
+            &vote_account,
+            &identity_pda,
+            550,
+        );
+        let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+
+        let vote_state = v4_state(result.get_account(&vote_account).unwrap());
+        assert_eq!(vote_state.inflation_rewards_commission_bps, 550);
+        assert_eq!(vote_state.block_revenue_commission_bps, 10_000);
+    }
+
+    #[test]
+    fn test_set_block_revenue_commission() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+        // A keypair withdrawer signs the outer instruction instead.
+        let authorized_withdrawer = Pubkey::new_unique();
+        let (vote_account, accounts) = setup_commission(authorized_withdrawer);
+
+        let ix = ProgramInstruction::set_block_revenue_commission(
+            &program_id,
+            &vote_account,
+            &authorized_withdrawer,
+            2_525,
+        );
+        let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+
+        let vote_state = v4_state(result.get_account(&vote_account).unwrap());
+        assert_eq!(vote_state.inflation_rewards_commission_bps, 1_000);
+        assert_eq!(vote_state.block_revenue_commission_bps, 2_525);
+    }
+
+    #[test]
+    fn test_set_commission_after_create() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+        let (_, _, accounts) = setup(&program_id, &mollusk);
+        let (identity_pda, _) = get_identity_pda(&program_id);
+        let vote_account = accounts[1].0;
+
+        let create_ix = ProgramInstruction::create(
+            &program_id,
+            &accounts[0].0,
+            &vote_account,
+            &Pubkey::new_unique(),
+            &identity_pda,
+            10,
+        );
+        let instructions = [
+            create_ix,
+            ProgramInstruction::set_inflation_rewards_commission(
+                &program_id,
+                &vote_account,
+                &identity_pda,
+                MAX_COMMISSION_BPS,
+            ),
+            ProgramInstruction::set_block_revenue_commission(
+                &program_id,
+                &vote_account,
+                &identity_pda,
+                0,
+            ),
+        ];
+        let result = mollusk.process_instruction_chain(&instructions, &accounts);
+        assert!(result.program_result.is_ok());
+
+        let vote_state = v4_state(result.get_account(&vote_account).unwrap());
+        assert_eq!(
+            vote_state.inflation_rewards_commission_bps,
+            MAX_COMMISSION_BPS
+        );
+        assert_eq!(vote_state.block_revenue_commission_bps, 0);
+    }
+
+    #[test]
+    fn fail_set_commission_out_of_range() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+        let (identity_pda, _) = get_identity_pda(&program_id);
+        let (vote_account, accounts) = setup_commission(identity_pda);
+
+        for ix in [
+            ProgramInstruction::set_inflation_rewards_commission(
+                &program_id,
+                &vote_account,
+                &identity_pda,
+                MAX_COMMISSION_BPS + 1,
+            ),
+            ProgramInstruction::set_block_revenue_commission(
+                &program_id,
+                &vote_account,
+                &identity_pda,
+                u16::MAX,
+            ),
+        ] {
+            mollusk.process_and_validate_instruction(
+                &ix,
+                &accounts,
+                &[Check::err(TestProgramError::CommissionOutOfRange.into())],
+            );
+        }
+    }
+
+    #[test]
+    fn fail_set_commission_wrong_withdrawer() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+        let (identity_pda, _) = get_identity_pda(&program_id);
+        let (vote_account, mut accounts) = setup_commission(identity_pda);
+
+        let impostor = Pubkey::new_unique();
+        accounts[1] = (impostor, Account::default());
+        let ix = ProgramInstruction::set_inflation_rewards_commission(
+            &program_id,
+            &vote_account,
+            &impostor,
+            550,
+        );
+        mollusk.process_and_validate_instruction(
+            &ix,
+            &accounts,
+            &[Check::instruction_err(
+                InstructionError::MissingRequiredSignature,
+            )],
+        );
+    }
+
+    #[test]
+    fn fail_set_commission_feature_disabled() {
+        let program_id = Pubkey::new_unique();
+        let mut mollusk = Mollusk::new(&program_id, "simd_0185");
+        mollusk.feature_set.deactivate(&vote_state_v4::id());
+        let (identity_pda, _) = get_identity_pda(&program_id);
+        let (vote_account, accounts) = setup_commission(identity_pda);
+
+        // Without v4 the Vote Program has no basis-point commissions.
+        let ix = ProgramInstruction::set_inflation_rewards_commission(
+            &program_id,
+            &vote_account,
+            &identity_pda,
+            550,
+        );
+        mollusk.process_and_validate_instruction(
+            &ix,
+            &accounts,
+            &[Check::instruction_err(
+                InstructionError::InvalidInstructionData,
+            )],
+        );
+    }
+
+    /// A v4 vote account whose authorized withdrawer is the identity PDA, a
+    /// rent-exempt system account, and the accounts a `Set*Collector`
+    /// instruction needs to make the system account a collector.
+    fn setup_collector(
+        program_id: &Pubkey,
+        mollusk: &Mollusk,
+    ) -> (Pubkey, Pubkey, Vec<(Pubkey, Account)>) {
+        let (identity_pda, _) = get_identity_pda(program_id);
+        let (vote_account, mut accounts) = setup_commission(identity_pda);
+        let collector = Pubkey::new_unique();
+        accounts.push((
+            collector,
+            Account::new(
+                mollusk.sysvars.rent.minimum_balance(0),
+                0,
+                &solana_sdk_ids::system_program::ID,
+            ),
+        ));
+        (vote_account, collector, accounts)
+    }
+
+    #[test]
+    fn test_collectors_default_after_create() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+        let (create_ix, _, accounts) = setup(&program_id, &mollusk);
+        let vote_account = accounts[1].0;
+
+        let result =
+            mollusk.process_and_validate_instruction(&create_ix, &accounts, &[Check::success()]);
+
+        // SIMD-0185: inflation rewards go to the vote account and block
+        // revenue to the node, which is the identity PDA.
+        let vote_state = v4_state(result.get_account(&vote_account).unwrap());
+        let (identity_pda, _) = get_identity_pda(&program_id);
+        assert_eq!(vote_state.inflation_rewards_collector, vote_account);
+        assert_eq!(vote_state.block_revenue_collector, identity_pda);
+        assert_eq!(vote_state.block_revenue_commission_bps, 10_000);
+    }
+
+    #[test]
+    fn test_set_inflation_rewards_collector() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+        let (vote_account, collector, accounts) = setup_collector(&program_id, &mollusk);
+        let (identity_pda, _) = get_identity_pda(&program_id);
+
+        let ix = ProgramInstruction::set_inflation_rewards_collector(
+            &program_id,
+            &vote_account,
+            &collector,
+            &identity_pda,
+        );
+        let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+
+        let vote_state = v4_state(result.get_account(&vote_account).unwrap());
+        assert_eq!(vote_state.inflation_rewards_collector, collector);
+        assert_eq!(vote_state.block_revenue_collector, vote_state.node_pubkey);
+    }
+
+    #[test]
+    fn test_set_block_revenue_collector() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+        let (vote_account, collector, accounts) = setup_collector(&program_id, &mollusk);
+        let (identity_pda, _) = get_identity_pda(&program_id);
+
+        let ix = ProgramInstruction::set_block_revenue_collector(
+            &program_id,
+            &vote_account,
+            &collector,
+            &identity_pda,
+        );
+        let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+        let vote_state = v4_state(result.get_account(&vote_account).unwrap());
+        assert_eq!(vote_state.inflation_rewards_collector, vote_account);
+        assert_eq!(vote_state.block_revenue_collector, collector);
+
+        // The vote account itself is always an allowed collector.
+        let ix = ProgramInstruction::set_block_revenue_collector(
+            &program_id,
+            &vote_account,
+            &vote_account,
+            &identity_pda,
+        );
+        let result = mollusk.process_and_validate_instruction(
+            &ix,
+            &result.resulting_accounts,
+            &[Check::success()],
+        );
+        let vote_state = v4_state(result.get_account(&vote_account).unwrap());
+        assert_eq!(vote_state.block_revenue_collector, vote_account);
+    }
+
+    /// Run `SetBlockRevenueCollector` after `tamper` has changed the new
+    /// collector account, and expect the Vote Program to reject it.
+    fn assert_collector_err(tamper: impl FnOnce(&mut Account), err: InstructionError) {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+        let (vote_account, collector, mut accounts) = setup_collector(&program_id, &mollusk);
+        let (identity_pda, _) = get_identity_pda(&program_id);
+        tamper(&mut accounts.last_mut().unwrap().1);
+
+        let ix = ProgramInstruction::set_block_revenue_collector(
+            &program_id,
+            &vote_account,
+            &collector,
+            &identity_pda,
+        );
+        mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::instruction_err(err)]);
+    }
+
+    #[test]
+    fn fail_set_collector_not_system_owned() {
+        assert_collector_err(
+            |collector| collector.owner = Pubkey::new_unique(),
+            InstructionError::InvalidAccountOwner,
+        );
+    }
+
+    #[test]
+    fn fail_set_collector_not_rent_exempt() {
+        assert_collector_err(
+            |collector| collector.lamports = 1,
+            InstructionError::InsufficientFunds,
+        );
+    }
+
+    /// A v4 vote account with `lamports`, whose authorized withdrawer is
+    /// `authorized_withdrawer`, and the accounts `Withdraw` needs to empty it
+    /// into a fresh recipient.
+    fn setup_withdraw(
+        authorized_withdrawer: Pubkey,
+        lamports: u64,
+    ) -> (Pubkey, Pubkey, Vec<(Pubkey, Account)>) {
+        let (vote_account, account) = VoteAccountFixture::v4()
+            .with_withdrawer(authorized_withdrawer)
+            .with_lamports(lamports)
+            .build();
+        let recipient = Pubkey::new_unique();
+        let accounts = vec![
+            (vote_account, account),
+            (recipient, Account::default()),
+            (authorized_withdrawer, Account::default()),
+            create_keyed_account_for_builtin_program(&solana_sdk_ids::vote::ID, "vote_program"),
+        ];
+        (vote_account, recipient, accounts)
+    }
+
+    #[test]
+    fn test_withdraw() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+        let (identity_pda, _) = get_identity_pda(&program_id);
+        let (vote_account, recipient, accounts) = setup_withdraw(identity_pda, 5_000_000_000);
+
+        let ix =
+            ProgramInstruction::withdraw(&program_id, &vote_account, &recipient, &identity_pda);
+        let result = mollusk.process_and_validate_instruction(
+            &ix,
+            &accounts,
+            &[
+                Check::success(),
+                Check::account(&vote_account).lamports(0).build(),
+                Check::account(&recipient).lamports(5_000_000_000).build(),
+            ],
+        );
+
+        // Closing a v4 account clears its data.
+        let data = &result.get_account(&vote_account).unwrap().data;
+        assert!(data.iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn test_withdraw_after_create() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+        let (_, _, mut accounts) = setup(&program_id, &mollusk);
+        let payer = accounts[0].0;
+        let vote_account = accounts[1].0;
+        // A keypair withdrawer, as the client creates, signs the outer
+        // instruction.
+        let authorized_withdrawer = Pubkey::new_unique();
+
+        let create_ix = ProgramInstruction::create(
+            &program_id,
+            &payer,
+            &vote_account,
+            &Pubkey::new_unique(),
+            &authorized_withdrawer,
+            10,
+        );
+        let withdraw_ix = ProgramInstruction::withdraw(
+            &program_id,
+            &vote_account,
+            &payer,
+            &authorized_withdrawer,
+        );
+        accounts.push((authorized_withdrawer, Account::default()));
+        let payer_lamports = accounts[0].1.lamports;
+
+        // The payer gets back the rent it paid to create the account.
+        let result = mollusk.process_instruction_chain(&[create_ix, withdraw_ix], &accounts);
+        assert!(result.program_result.is_ok());
+        assert_eq!(result.get_account(&vote_account).unwrap().lamports, 0);
+        assert_eq!(result.get_account(&payer).unwrap().lamports, payer_lamports);
+    }
+
+    #[test]
+    fn fail_withdraw_wrong_withdrawer() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+        let (identity_pda, _) = get_identity_pda(&program_id);
+        let (vote_account, recipient, mut accounts) = setup_withdraw(identity_pda, 5_000_000_000);
+
+        let impostor = Pubkey::new_unique();
+        accounts[2] = (impostor, Account::default());
+        let ix = ProgramInstruction::withdraw(&program_id, &vote_account, &recipient, &impostor);
+        mollusk.process_and_validate_instruction(
+            &ix,
+            &accounts,
             &[Check::instruction_err(
-                InstructionError::ProgramFailedToComplete,
+                InstructionError::MissingRequiredSignature,
             )],
         );
     }
+
+    #[test]
+    fn fail_withdraw_wrong_owner() {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+        let (identity_pda, _) = get_identity_pda(&program_id);
+        let (vote_account, recipient, mut accounts) = setup_withdraw(identity_pda, 5_000_000_000);
+
+        accounts[0].1.owner = Pubkey::new_unique();
+        let ix =
+            ProgramInstruction::withdraw(&program_id, &vote_account, &recipient, &identity_pda);
+        mollusk.process_and_validate_instruction(
+            &ix,
+            &accounts,
+            &[Check::err(TestProgramError::WrongAccountOwner.into())],
+        );
+    }
+
+    fn assert_decode_err(data: &[u8], err: TestProgramError) {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0185");
+        let instruction = Instruction::new_with_bytes(program_id, data, vec![]);
+        mollusk.process_and_validate_instruction(&instruction, &[], &[Check::err(err.into())]);
+    }
+
+    #[test]
+    fn fail_empty_instruction_data() {
+        assert_decode_err(&[], TestProgramError::EmptyInstructionData);
+    }
+
+    #[test]
+    fn fail_unknown_instruction() {
+        assert_decode_err(&[7], TestProgramError::UnknownInstruction);
+    }
+
+    #[test]
+    fn fail_truncated_instruction_data() {
+        let ix = ProgramInstruction::create(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            10,
+        );
+        let data = ix.data;
+        assert_decode_err(
+            &data[..data.len() - 1],
+            TestProgramError::TruncatedInstructionData,
+        );
+    }
 }
@@ -0,0 +1,302 @@
+//! SIMD-0185 migration: once `vote_state_v4` activates, Vote Program
+//! instructions that write a v3 (or v1_14_11) vote account rewrite it as v4,
+//! deriving the new fields from the old state.
+//!
+//! These only run the builtin Vote Program, so unlike the program tests they
+//! run on the host without an SBF build.
+
+use {
+    agave_feature_set::vote_state_v4,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_instruction::Instruction,
+    solana_program_error::ProgramError,
+    solana_pubkey::Pubkey,
+    solana_vote_interface::{
+        instruction::{authorize, tower_sync, update_commission, withdraw},
+        state::{
+            TowerSync, VoteAuthorize, VoteState1_14_11, VoteStateV3, VoteStateV4, VoteStateVersions,
+        },
+    },
+    vote_fixture::VoteAccountFixture,
+};
+
+/// Far enough in that `SlotHashes` has slots to vote on.
+const SLOT: u64 = 16;
+
+struct Migration {
+    mollusk: Mollusk,
+    vote_pubkey: Pubkey,
+    node: Pubkey,
+    voter: Pubkey,
+    withdrawer: Pubkey,
+    accounts: Vec<(Pubkey, Account)>,
+}
+
+impl Migration {
+    /// A vote account at 15% commission in `fixture`'s layout, with
+    /// `vote_state_v4` inactive.
+    fn new(fixture: VoteAccountFixture) -> Self {
+        let mut mollusk = Mollusk::default();
+        mollusk.feature_set.deactivate(&vote_state_v4::id());
+        mollusk.warp_to_slot(SLOT);
+
+        let node = Pubkey::new_unique();
+        let voter = Pubkey::new_unique();
+        let withdrawer = Pubkey::new_unique();
+        let (vote_pubkey, vote_account) = fixture
+            .with_node(node)
+            .with_voters(&[(0, voter)])
+            .with_withdrawer(withdrawer)
+            .with_commission_bps(1_500)
+            .build();
+
+        let accounts = vec![
+            (vote_pubkey, vote_account),
+            (node, Account::default()),
+            (voter, Account::default()),
+            (withdrawer, Account::default()),
+            mollusk.sysvars.keyed_account_for_clock_sysvar(),
+        ];
+        Self {
+            mollusk,
+            vote_pubkey,
+            node,
+            voter,
+            withdrawer,
+            accounts,
+        }
+    }
+
+    fn activate_v4(&mut self) {
+        self.mollusk
+            .feature_set
+            .activate(&vote_state_v4::id(), SLOT);
+    }
+
+    fn vote_account(&self) -> &Account {
+        &self.accounts[0].1
+    }
+
+    fn state(&self) -> VoteStateVersions {
+        VoteStateVersions::deserialize(&self.vote_account().data).unwrap()
+    }
+
+    /// Run `instruction`, expecting `check`, and keep the resulting accounts.
+    fn run(&mut self, instruction: &Instruction, check: Check) {
+        let result =
+            self.mollusk
+                .process_and_validate_instruction(instruction, &self.accounts, &[check]);
+        self.accounts = result.resulting_accounts;
+    }
+
+    /// The account was rewritten as v4 with the fields SIMD-0185 derives from
+    /// the old state, and is rent-exempt at the v4 size.
+    fn assert_migrated(&self) -> Box<VoteStateV4> {
+        let VoteStateVersions::V4(state) = self.state() else {
+            panic!("expected v4 vote state, got {:?}", self.state());
+        };
+        assert_eq!(state.node_pubkey, self.node);
+        assert_eq!(state.inflation_rewards_collector, self.vote_pubkey);
+        assert_eq!(state.block_revenue_collector, self.node);
+        assert_eq!(state.block_revenue_commission_bps, 10_000);
+        assert_eq!(state.pending_delegator_rewards, 0);
+        assert_eq!(state.bls_pubkey_compressed, None);
+
+        let account = self.vote_account();
+        assert_eq!(account.data.len(), VoteStateV4::size_of());
+        assert!(self
+            .mollusk
+            .sysvars
+            .rent
+            .is_exempt(account.lamports, account.data.len()));
+        state
+    }
+}
+
+#[test]
+fn authorize_migrates_to_v4() {
+    let mut migration = Migration::new(VoteAccountFixture::v3());
+    let new_withdrawer = Pubkey::new_unique();
+    migration
+        .accounts
+        .push((new_withdrawer, Account::default()));
+
+    // Before activation the account keeps its v3 layout.
+    let ix = authorize(
+        &migration.vote_pubkey,
+        &migration.withdrawer,
+        &new_withdrawer,
+        VoteAuthorize::Withdrawer,
+    );
+    migration.run(&ix, Check::success());
+    let VoteStateVersions::V3(state) = migration.state() else {
+        panic!("expected v3 vote state");
+    };
+    assert_eq!(state.authorized_withdrawer, new_withdrawer);
+
+    migration.activate_v4();
+    let new_voter = Pubkey::new_unique();
+    migration.accounts.push((new_voter, Account::default()));
+    let ix = authorize(
+        &migration.vote_pubkey,
+        &new_withdrawer,
+        &new_voter,
+        VoteAuthorize::Voter,
+    );
+    migration.run(&ix, Check::success());
+
+    let state = migration.assert_migrated();
+    assert_eq!(state.authorized_withdrawer, new_withdrawer);
+    assert_eq!(state.inflation_rewards_commission_bps, 1_500);
+    assert_eq!(
+        state.authorized_voters.last().map(|(_, voter)| *voter),
+        Some(new_voter)
+    );
+}
+
+#[test]
+fn update_commission_migrates_to_v4() {
+    let mut migration = Migration::new(VoteAccountFixture::v3());
+
+    let ix = update_commission(&migration.vote_pubkey, &migration.withdrawer, 10);
+    migration.run(&ix, Check::success());
+    let VoteStateVersions::V3(state) = migration.state() else {
+        panic!("expected v3 vote state");
+    };
+    assert_eq!(state.commission, 10);
+
+    // The percentage becomes basis points.
+    migration.activate_v4();
+    let ix = update_commission(&migration.vote_pubkey, &migration.withdrawer, 20);
+    migration.run(&ix, Check::success());
+
+    let state = migration.assert_migrated();
+    assert_eq!(state.inflation_rewards_commission_bps, 2_000);
+}
+
+#[test]
+fn vote_migrates_to_v4() {
+    let mut migration = Migration::new(VoteAccountFixture::v3());
+
+    let ix = tower_sync(
+        &migration.vote_pubkey,
+        &migration.voter,
+        TowerSync::from(vec![(SLOT - 2, 1)]),
+    );
+    migration.run(&ix, Check::success());
+    assert!(matches!(migration.state(), VoteStateVersions::V3(_)));
+
+    migration.activate_v4();
+    let ix = tower_sync(
+        &migration.vote_pubkey,
+        &migration.voter,
+        TowerSync::from(vec![(SLOT - 2, 2), (SLOT - 1, 1)]),
+    );
+    migration.run(&ix, Check::success());
+
+    // The votes cast before activation carry over.
+    let state = migration.assert_migrated();
+    assert_eq!(state.inflation_rewards_commission_bps, 1_500);
+    assert_eq!(
+        state
+            .votes
+            .iter()
+            .map(|vote| vote.slot())
+            .collect::<Vec<_>>(),
+        [SLOT - 2, SLOT - 1]
+    );
+}
+
+#[test]
+fn withdraw_leaves_layout_until_next_write() {
+    let mut migration = Migration::new(VoteAccountFixture::v3().with_lamports(5_000_000_000));
+    let recipient = Pubkey::new_unique();
+    migration.accounts.push((recipient, Account::default()));
+    let rent_exempt = migration
+        .mollusk
+        .sysvars
+        .rent
+        .minimum_balance(VoteStateV4::size_of());
+
+    let ix = withdraw(
+        &migration.vote_pubkey,
+        &migration.withdrawer,
+        1_000_000_000,
+        &recipient,
+    );
+    migration.run(&ix, Check::success());
+
+    // A partial withdrawal only moves lamports, so the v3 layout survives
+    // activation until an instruction writes the state.
+    migration.activate_v4();
+    let excess = migration.vote_account().lamports - rent_exempt;
+    let ix = withdraw(
+        &migration.vote_pubkey,
+        &migration.withdrawer,
+        excess,
+        &recipient,
+    );
+    migration.run(&ix, Check::success());
+    assert!(matches!(migration.state(), VoteStateVersions::V3(_)));
+    assert_eq!(migration.vote_account().lamports, rent_exempt);
+
+    // The remaining balance still covers the v4 layout.
+    let ix = update_commission(&migration.vote_pubkey, &migration.withdrawer, 15);
+    migration.run(&ix, Check::success());
+    migration.assert_migrated();
+
+    // Closing the account clears all of its data, as SIMD-0185 requires.
+    let ix = withdraw(
+        &migration.vote_pubkey,
+        &migration.withdrawer,
+        rent_exempt,
+        &recipient,
+    );
+    migration.run(&ix, Check::success());
+    assert_eq!(migration.vote_account().lamports, 0);
+    assert!(migration.vote_account().data.iter().all(|byte| *byte == 0));
+}
+
+#[test]
+fn v1_14_11_resizes_to_v4() {
+    let space = VoteState1_14_11::size_of();
+    let rent = Mollusk::default().sysvars.rent;
+    let mut migration = Migration::new(
+        VoteAccountFixture::v1_14_11().with_lamports(rent.minimum_balance(VoteStateV4::size_of())),
+    );
+
+    migration.activate_v4();
+    let ix = update_commission(&migration.vote_pubkey, &migration.withdrawer, 20);
+    migration.run(&ix, Check::success());
+
+    // The account grows from the v1_14_11 size to the v4 size.
+    assert!(space < VoteStateV4::size_of());
+    let state = migration.assert_migrated();
+    assert_eq!(state.inflation_rewards_commission_bps, 2_000);
+}
+
+#[test]
+fn fail_v1_14_11_not_rent_exempt_at_v4_size() {
+    let space = VoteState1_14_11::size_of();
+    let rent = Mollusk::default().sysvars.rent;
+    let mut migration =
+        Migration::new(VoteAccountFixture::v1_14_11().with_lamports(rent.minimum_balance(space)));
+
+    // Before activation the v3 target falls back to the old layout.
+    let ix = update_commission(&migration.vote_pubkey, &migration.withdrawer, 10);
+    migration.run(&ix, Check::success());
+    assert!(matches!(migration.state(), VoteStateVersions::V1_14_11(_)));
+    assert_eq!(migration.vote_account().data.len(), space);
+
+    // SIMD-0185 has no such fallback.
+    migration.activate_v4();
+    let ix = update_commission(&migration.vote_pubkey, &migration.withdrawer, 20);
+    migration.run(&ix, Check::err(ProgramError::AccountNotRentExempt));
+}
+
+#[test]
+fn v3_and_v4_share_a_size() {
+    // So a v3 account never needs more rent to migrate.
+    assert_eq!(VoteStateV3::size_of(), VoteStateV4::size_of());
+}
@@ -0,0 +1,78 @@
+//! Compute units for each SIMD-0321 instruction variant, with the r2 data
+//! pointer feature on and off, alongside the classic input-buffer program
+//! reading the same data. Run with `make bench-simd-0321`.
+
+use {
+    agave_feature_set::provide_instruction_data_offset_in_vm_r2,
+    cu_bench::CuBench,
+    mollusk_svm::Mollusk,
+    simd_0321_interface::{build_instruction, EasterEgg, MAX_INSTRUCTION_DATA_LEN},
+    solana_account::Account,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_pubkey::Pubkey,
+};
+
+fn main() {
+    let program_id = Pubkey::new_unique();
+    let classic_program_id = Pubkey::new_unique();
+    let mollusk = || {
+        let mut mollusk = Mollusk::new(&program_id, "simd_0321");
+        mollusk.add_program(&classic_program_id, "simd_0321_classic");
+        mollusk
+    };
+
+    // Eight 1 KiB accounts in front of the instruction data.
+    let accounts = (0..8)
+        .map(|_| {
+            (
+                Pubkey::new_unique(),
+                Account::new(1, 1024, &Pubkey::new_unique()),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut bench = CuBench::new(
+        "simd-0321",
+        &[(
+            "instruction_data_in_r2",
+            provide_instruction_data_offset_in_vm_r2::id(),
+        )],
+    );
+    for (name, data) in [
+        ("raw_bytes", vec![0xDE, 0xAD, 0xBE, 0xEF]),
+        ("raw_bytes_1kib", vec![0xAB; 1024]),
+        ("raw_bytes_10kib", vec![0xAB; MAX_INSTRUCTION_DATA_LEN]),
+        (
+            "easter_egg",
+            EasterEgg::compose("Hoot hoot! You found the secret owl!".into()).encode(),
+        ),
+    ] {
+        let instruction = build_instruction(&program_id, data);
+        bench.bench(name, mollusk, |mollusk| {
+            mollusk.process_instruction(&instruction, &[])
+        });
+    }
+
+    for (name, program_id, accounts) in [
+        ("raw_bytes_8_accounts", program_id, &accounts[..]),
+        ("classic/raw_bytes", classic_program_id, &[]),
+        (
+            "classic/raw_bytes_8_accounts",
+            classic_program_id,
+            &accounts[..],
+        ),
+    ] {
+        let instruction = Instruction::new_with_bytes(
+            program_id,
+            &[0xDE, 0xAD, 0xBE, 0xEF],
+            accounts
+                .iter()
+                .map(|(pubkey, _)| AccountMeta::new_readonly(*pubkey, false))
+                .collect(),
+        );
+        bench.bench(name, mollusk, |mollusk| {
+            mollusk.process_instruction(&instruction, accounts)
+        });
+    }
+    bench.finish();
+}
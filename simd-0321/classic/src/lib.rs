@@ -0,0 +1,22 @@
+//! # SIMD-0321 (classic)
+//!
+//! Reference program for SIMD-0321 that finds the instruction data the
+//! classic way, by walking the serialized input buffer past every account.
+//! The `simd-0321` tests compare the data it logs against the r2 program's.
+
+#![allow(clippy::missing_safety_doc)]
+
+#[no_mangle]
+pub unsafe extern "C" fn entrypoint(input: *mut u8) -> u64 {
+    let (_, _, instruction_data) = solana_program_entrypoint::deserialize(input);
+    solana_msg::msg!(
+        "{} bytes, checksum {:#010x}",
+        instruction_data.len(),
+        simd_0321_interface::checksum(instruction_data)
+    );
+    simd_0321_interface::log_data(instruction_data);
+    solana_program_entrypoint::SUCCESS
+}
+
+solana_program_entrypoint::custom_heap_default!();
+solana_program_entrypoint::custom_panic_default!();
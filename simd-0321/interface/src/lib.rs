@@ -51,6 +51,33 @@ impl EasterEgg {
     }
 }
 
+/// The runtime's limit on instruction data, 10 KiB.
+pub const MAX_INSTRUCTION_DATA_LEN: usize = 10 * 1024;
+
+/// 32-bit FNV-1a checksum of `data`, logged by the program so clients can
+/// check the bytes it read without logging all of them.
+pub fn checksum(data: &[u8]) -> u32 {
+    data.iter().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ *byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Log `data` as a `Program data: <base64>` line, so tests can compare the
+/// exact bytes the program read.
+pub fn log_data(data: &[u8]) {
+    #[cfg(target_os = "solana")]
+    unsafe {
+        let fields: &[&[u8]] = &[data];
+        solana_define_syscall::definitions::sol_log_data(
+            fields.as_ptr() as *const u8,
+            fields.len() as u64,
+        );
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    core::hint::black_box(data);
+}
+
 pub fn build_instruction(program_id: &Pubkey, data: Vec<u8>) -> Instruction {
     Instruction {
         program_id: *program_id,
@@ -61,7 +88,10 @@ pub fn build_instruction(program_id: &Pubkey, data: Vec<u8>) -> Instruction {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use {
+        super::*,
+        proptest::{collection::vec, prelude::*},
+    };
 
     #[test]
     fn too_short() {
@@ -95,6 +125,13 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fnv1a_checksum() {
+        assert_eq!(checksum(&[]), 0x811c_9dc5);
+        assert_eq!(checksum(b"a"), 0xe40c_292c);
+        assert_eq!(checksum(b"foobar"), 0xbf9c_f968);
+    }
+
     #[test]
     fn happy_path() {
         let msg = "You found the easter egg!";
@@ -104,4 +141,47 @@ mod tests {
         assert_eq!(decoded.key, KEY_AS_PUBKEY.to_bytes());
         assert_eq!(decoded.message, msg);
     }
+
+    proptest! {
+        #[test]
+        fn decode_arbitrary_bytes(input in vec(any::<u8>(), 0..256)) {
+            if let Ok(egg) = EasterEgg::try_decode(&input) {
+                prop_assert_eq!(egg.encode(), input);
+            }
+        }
+
+        #[test]
+        fn decode_arbitrary_payload(payload in vec(any::<u8>(), 0..256)) {
+            let mut input = EasterEgg::compose(String::new()).encode();
+            input.extend_from_slice(&payload);
+            match String::from_utf8(payload) {
+                Ok(message) => {
+                    prop_assert_eq!(EasterEgg::try_decode(&input).unwrap().message, message)
+                }
+                Err(_) => prop_assert_eq!(
+                    EasterEgg::try_decode(&input).unwrap_err(),
+                    ProgramError::InvalidInstructionData,
+                ),
+            }
+        }
+
+        #[test]
+        fn encode_decode_roundtrip(message in ".*") {
+            let decoded =
+                EasterEgg::try_decode(&EasterEgg::compose(message.clone()).encode()).unwrap();
+            prop_assert_eq!(decoded.code, CODE);
+            prop_assert_eq!(decoded.key, KEY_AS_PUBKEY.to_bytes());
+            prop_assert_eq!(decoded.message, message);
+        }
+
+        #[test]
+        fn reject_mutated_code_or_key(message in ".*", index in 0..40usize, flip in 1..=u8::MAX) {
+            let mut encoded = EasterEgg::compose(message).encode();
+            encoded[index] ^= flip;
+            prop_assert_eq!(
+                EasterEgg::try_decode(&encoded).unwrap_err(),
+                ProgramError::InvalidInstructionData,
+            );
+        }
+    }
 }
@@ -1,17 +1,31 @@
 //! # SIMD-0321
 //!
 //! Test program that reads instruction data using the r2 register pointer.
+//! Accounts are accepted but ignored, since r2 points at the instruction data
+//! wherever the accounts leave it in the input buffer.
 
 #![allow(clippy::arithmetic_side_effects)]
 #![allow(clippy::missing_safety_doc)]
 
+use simd_0321_interface::{checksum, log_data, EasterEgg};
+
+/// Longer instruction data is only logged by its checksum.
+const MAX_LOGGED_BYTES: usize = 64;
+
 #[no_mangle]
 pub unsafe extern "C" fn entrypoint(_input: *mut u8, instruction_data_addr: *const u8) -> u64 {
     let instruction_data_len = *((instruction_data_addr as u64 - 8) as *const u64);
     let instruction_data =
         core::slice::from_raw_parts(instruction_data_addr, instruction_data_len as usize);
 
-    match simd_0321_interface::EasterEgg::try_decode(instruction_data) {
+    solana_msg::msg!(
+        "{} bytes, checksum {:#010x}",
+        instruction_data.len(),
+        checksum(instruction_data)
+    );
+    log_data(instruction_data);
+
+    match EasterEgg::try_decode(instruction_data) {
         Ok(egg) => {
             solana_msg::msg!("A secret has been unlocked");
             solana_msg::msg!("");
@@ -24,7 +38,10 @@ pub unsafe extern "C" fn entrypoint(_input: *mut u8, instruction_data_addr: *con
             solana_msg::msg!("");
             solana_msg::msg!("~ {} ~", egg.message);
         }
-        Err(_) => solana_msg::msg!("{:?}", instruction_data),
+        Err(_) if instruction_data.len() <= MAX_LOGGED_BYTES => {
+            solana_msg::msg!("{:?}", instruction_data)
+        }
+        Err(_) => (),
     }
 
     solana_program_entrypoint::SUCCESS
@@ -37,14 +54,22 @@ solana_program_entrypoint::custom_panic_default!();
 mod tests {
     use {
         agave_feature_set::provide_instruction_data_offset_in_vm_r2,
+        base64::{prelude::BASE64_STANDARD, Engine},
         mollusk_svm::{result::Check, Mollusk},
-        simd_0321_interface::EasterEgg,
-        solana_instruction::{error::InstructionError, Instruction},
+        rand::{rngs::StdRng, Rng, SeedableRng},
+        simd_0321_interface::{checksum, EasterEgg, MAX_INSTRUCTION_DATA_LEN},
+        solana_account::Account,
+        solana_instruction::{error::InstructionError, AccountMeta, Instruction},
         solana_pubkey::Pubkey,
         solana_svm_log_collector::LogCollector,
         std::{cell::RefCell, rc::Rc},
     };
 
+    const CASES: usize = 64;
+
+    /// The largest account data the runtime allows, 10 MiB.
+    const MAX_ACCOUNT_DATA_LEN: usize = 10 * 1024 * 1024;
+
     fn setup(data: &[u8]) -> (Mollusk, Instruction, Rc<RefCell<LogCollector>>) {
         let program_id = Pubkey::new_unique();
         let mut mollusk = Mollusk::new(&program_id, "simd_0321");
@@ -54,15 +79,80 @@ mod tests {
         (mollusk, instruction, log_collector)
     }
 
+    fn checksum_log(data: &[u8]) -> String {
+        format!(
+            "Program log: {} bytes, checksum {:#010x}",
+            data.len(),
+            checksum(data)
+        )
+    }
+
     #[test]
     fn bytes() {
         let (mollusk, instruction, log_collector) = setup(&[0xDE, 0xAD]);
         mollusk.process_and_validate_instruction(&instruction, &[], &[Check::success()]);
 
         let logs = log_collector.borrow().get_recorded_content().to_vec();
+        assert!(logs.contains(&checksum_log(&[0xDE, 0xAD])));
         assert!(logs.iter().any(|log| log.contains("[222, 173]")),);
     }
 
+    #[test]
+    fn max_size_data() {
+        let data = (0..MAX_INSTRUCTION_DATA_LEN)
+            .map(|i| i as u8)
+            .collect::<Vec<_>>();
+        let (mollusk, instruction, log_collector) = setup(&data);
+        mollusk.process_and_validate_instruction(&instruction, &[], &[Check::success()]);
+
+        let logs = log_collector.borrow().get_recorded_content().to_vec();
+        assert!(logs.contains(&checksum_log(&data)));
+        // Too long to log byte by byte.
+        assert!(!logs.iter().any(|log| log.contains("[0, 1, 2")));
+    }
+
+    #[test]
+    fn accounts() {
+        let data = (0..MAX_INSTRUCTION_DATA_LEN)
+            .map(|i| (i * 7) as u8)
+            .collect::<Vec<_>>();
+        let (mollusk, mut instruction, log_collector) = setup(&data);
+
+        let owner = Pubkey::new_unique();
+        let small = (Pubkey::new_unique(), Account::new(1, 3, &owner));
+        let large = (
+            Pubkey::new_unique(),
+            Account::new(1, MAX_ACCOUNT_DATA_LEN, &owner),
+        );
+        let executable = (
+            Pubkey::new_unique(),
+            Account {
+                lamports: 1,
+                data: vec![0xEE; 101],
+                owner,
+                executable: true,
+                rent_epoch: 0,
+            },
+        );
+        instruction.accounts = vec![
+            AccountMeta::new(small.0, false),
+            AccountMeta::new_readonly(large.0, false),
+            // Duplicates are serialized as an index into the earlier accounts.
+            AccountMeta::new(small.0, false),
+            AccountMeta::new_readonly(executable.0, false),
+            AccountMeta::new_readonly(large.0, false),
+        ];
+
+        mollusk.process_and_validate_instruction(
+            &instruction,
+            &[small, large, executable],
+            &[Check::success()],
+        );
+
+        let logs = log_collector.borrow().get_recorded_content().to_vec();
+        assert!(logs.contains(&checksum_log(&data)));
+    }
+
     #[test]
     fn easter_egg() {
         let data = EasterEgg::compose("a warrior was here".into()).encode();
@@ -93,4 +183,105 @@ mod tests {
             )],
         );
     }
+
+    /// Random instruction data alongside up to 8 random accounts, some of them
+    /// repeated, so the instruction data lands at a different offset in the
+    /// input buffer each time.
+    fn random_instruction(rng: &mut StdRng) -> (Instruction, Vec<(Pubkey, Account)>) {
+        let mut data = vec![0; rng.gen_range(0..=MAX_INSTRUCTION_DATA_LEN)];
+        rng.fill(data.as_mut_slice());
+
+        let accounts = (0..rng.gen_range(0..=8))
+            .map(|_| {
+                let mut account = Account::new(
+                    rng.gen_range(0..1_000_000_000),
+                    rng.gen_range(0..=2048),
+                    &Pubkey::new_unique(),
+                );
+                rng.fill(account.data.as_mut_slice());
+                (Pubkey::new_unique(), account)
+            })
+            .collect::<Vec<_>>();
+        let metas = match accounts.len() {
+            0 => vec![],
+            n => (0..rng.gen_range(0..=12))
+                .map(|_| AccountMeta {
+                    pubkey: accounts[rng.gen_range(0..n)].0,
+                    is_signer: rng.gen(),
+                    is_writable: rng.gen(),
+                })
+                .collect(),
+        };
+
+        let instruction = Instruction::new_with_bytes(Pubkey::default(), &data, metas);
+        (instruction, accounts)
+    }
+
+    /// The instruction data `program_id` logged with `log_data`, its
+    /// checksum line, and the compute units it consumed.
+    fn logged_data(
+        mollusk: &mut Mollusk,
+        program_id: &Pubkey,
+        instruction: &Instruction,
+        accounts: &[(Pubkey, Account)],
+    ) -> (Vec<u8>, String, u64) {
+        // 10 KiB of data is longer in base64 than the default log limit.
+        let log_collector = LogCollector::new_ref_with_limit(None);
+        mollusk.logger = Some(log_collector.clone());
+        let instruction = Instruction {
+            program_id: *program_id,
+            ..instruction.clone()
+        };
+        let result =
+            mollusk.process_and_validate_instruction(&instruction, accounts, &[Check::success()]);
+
+        let logs = log_collector.borrow().get_recorded_content().to_vec();
+        let data = logs
+            .iter()
+            .find_map(|log| log.strip_prefix("Program data: "))
+            .map(|data| BASE64_STANDARD.decode(data).unwrap())
+            .expect("program logged its instruction data");
+        let checksum_log = logs
+            .iter()
+            .find(|log| log.contains("checksum"))
+            .cloned()
+            .expect("program logged its checksum");
+        (data, checksum_log, result.compute_units_consumed)
+    }
+
+    #[test]
+    fn matches_classic_entrypoint() {
+        let program_id = Pubkey::new_unique();
+        let classic_program_id = Pubkey::new_unique();
+        let mut mollusk = Mollusk::new(&program_id, "simd_0321");
+        mollusk.add_program(&classic_program_id, "simd_0321_classic");
+
+        let mut rng = StdRng::seed_from_u64(321);
+        let (mut r2_units, mut classic_units) = (0, 0);
+        for case in 0..CASES {
+            let (instruction, accounts) = random_instruction(&mut rng);
+            let (r2_data, r2_log, r2) =
+                logged_data(&mut mollusk, &program_id, &instruction, &accounts);
+            let (classic_data, _, classic) =
+                logged_data(&mut mollusk, &classic_program_id, &instruction, &accounts);
+
+            assert_eq!(r2_log, checksum_log(&instruction.data), "case {case}");
+            assert_eq!(r2_data, instruction.data, "case {case}");
+            assert_eq!(
+                r2_data,
+                classic_data,
+                "case {case}: {} bytes of data, {} accounts",
+                instruction.data.len(),
+                instruction.accounts.len()
+            );
+            println!(
+                "case {case:>2}: {:>5} bytes, {:>2} accounts: r2 {r2} CUs, classic {classic} CUs",
+                instruction.data.len(),
+                instruction.accounts.len()
+            );
+            r2_units += r2;
+            classic_units += classic;
+        }
+        println!("total: r2 {r2_units} CUs, classic {classic_units} CUs");
+    }
 }
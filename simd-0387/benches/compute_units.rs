@@ -0,0 +1,65 @@
+//! Compute units for each SIMD-0387 instruction variant, with
+//! `bls_pubkey_management_in_vote_account` on and off. Run with
+//! `make bench-simd-0387`.
+
+use {
+    agave_feature_set::bls_pubkey_management_in_vote_account,
+    cu_bench::CuBench,
+    mollusk_svm::{program::create_keyed_account_for_builtin_program, Mollusk},
+    simd_0387_interface::ProgramInstruction,
+    solana_account::Account,
+    solana_pubkey::Pubkey,
+    solana_vote_program::vote_state::create_bls_pubkey_and_proof_of_possession,
+    vote_fixture::VoteAccountFixture,
+};
+
+fn main() {
+    let program_id = Pubkey::new_unique();
+    let mollusk = || Mollusk::new(&program_id, "simd_0387");
+
+    let mut bench = CuBench::new(
+        "simd-0387",
+        &[(
+            "bls_pubkey_management",
+            bls_pubkey_management_in_vote_account::id(),
+        )],
+    );
+
+    // Set: vote program CPI, including proof of possession verification.
+    let authorized_voter = Pubkey::new_unique();
+    let (vote_pubkey, vote_account) = VoteAccountFixture::v4()
+        .with_node(authorized_voter)
+        .with_voters(&[(0, authorized_voter)])
+        .with_withdrawer(authorized_voter)
+        .build();
+    let (bls_pubkey_compressed, bls_proof_of_possession) =
+        create_bls_pubkey_and_proof_of_possession(&vote_pubkey);
+    let set_ix = ProgramInstruction::set(
+        &program_id,
+        &authorized_voter,
+        &vote_pubkey,
+        &Pubkey::new_unique(),
+        &bls_pubkey_compressed,
+        &bls_proof_of_possession,
+    );
+    bench.bench("set", mollusk, |mollusk| {
+        let accounts = [
+            (authorized_voter, Account::default()),
+            (vote_pubkey, vote_account.clone()),
+            mollusk.sysvars.keyed_account_for_clock_sysvar(),
+            create_keyed_account_for_builtin_program(&solana_sdk_ids::vote::ID, "vote_program"),
+        ];
+        mollusk.process_instruction(&set_ix, &accounts)
+    });
+
+    // View: read back the BLS pubkey.
+    let (vote_pubkey, vote_account) = VoteAccountFixture::v4()
+        .with_bls_key(bls_pubkey_compressed)
+        .build();
+    let view_ix = ProgramInstruction::view(&program_id, &vote_pubkey);
+    bench.bench("view", mollusk, |mollusk| {
+        mollusk.process_instruction(&view_ix, &[(vote_pubkey, vote_account.clone())])
+    });
+
+    bench.finish();
+}
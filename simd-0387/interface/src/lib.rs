@@ -1,13 +1,16 @@
 //! Interface for the SIMD-0387 test program.
 
 use {
+    program_error::TestProgramError,
     solana_instruction::{AccountMeta, Instruction},
+    solana_program_error::ProgramError,
     solana_pubkey::Pubkey,
     solana_vote_interface::state::{
         BLS_PROOF_OF_POSSESSION_COMPRESSED_SIZE, BLS_PUBLIC_KEY_COMPRESSED_SIZE,
     },
 };
 
+#[derive(Debug, PartialEq, Eq)]
 pub enum ProgramInstruction {
     /// Set a v4 vote account's BLS public key using the
     /// `Authorize::VoterWithBls` instruction.
@@ -16,8 +19,8 @@ pub enum ProgramInstruction {
     ///
     /// 0. `[s]` Authorized voter
     /// 1. `[w]` Vote account
-    /// 4. `[ ]` Clock sysvar
-    /// 6. `[ ]` Vote program
+    /// 2. `[ ]` Clock sysvar
+    /// 3. `[ ]` Vote program
     Set {
         new_authorized_voter: Pubkey,
         bls_pubkey_compressed: [u8; BLS_PUBLIC_KEY_COMPRESSED_SIZE],
@@ -37,28 +40,34 @@ impl ProgramInstruction {
     const SET: u8 = 0;
     const VIEW: u8 = 1;
 
-    pub fn decode(input: &[u8]) -> Self {
-        match input.first() {
-            Some(&Self::SET) => {
-                const OFFSET: usize = 1;
-                const VOTER_END: usize = OFFSET + size_of::<Pubkey>();
+    pub fn decode(input: &[u8]) -> Result<Self, ProgramError> {
+        let (&tag, rest) = input
+            .split_first()
+            .ok_or(TestProgramError::EmptyInstructionData)?;
+        match tag {
+            Self::SET => {
+                const VOTER_END: usize = size_of::<Pubkey>();
                 const BLS_PUBKEY_END: usize = VOTER_END + BLS_PUBLIC_KEY_COMPRESSED_SIZE;
                 const BLS_POP_END: usize = BLS_PUBKEY_END + BLS_PROOF_OF_POSSESSION_COMPRESSED_SIZE;
 
+                let payload = rest
+                    .get(..BLS_POP_END)
+                    .ok_or(TestProgramError::TruncatedInstructionData)?;
+
                 let new_authorized_voter =
-                    Pubkey::new_from_array(input[OFFSET..VOTER_END].try_into().unwrap());
-                let bls_pubkey_compressed = input[VOTER_END..BLS_PUBKEY_END].try_into().unwrap();
+                    Pubkey::new_from_array(payload[..VOTER_END].try_into().unwrap());
+                let bls_pubkey_compressed = payload[VOTER_END..BLS_PUBKEY_END].try_into().unwrap();
                 let bls_proof_of_possession =
-                    input[BLS_PUBKEY_END..BLS_POP_END].try_into().unwrap();
+                    payload[BLS_PUBKEY_END..BLS_POP_END].try_into().unwrap();
 
-                Self::Set {
+                Ok(Self::Set {
                     new_authorized_voter,
                     bls_pubkey_compressed,
                     bls_proof_of_possession,
-                }
+                })
             }
-            Some(&Self::VIEW) => Self::View,
-            _ => panic!("invalid instruction"),
+            Self::VIEW => Ok(Self::View),
+            _ => Err(TestProgramError::UnknownInstruction.into()),
         }
     }
 
@@ -103,7 +112,35 @@ impl ProgramInstruction {
 
 #[cfg(test)]
 mod tests {
-    use {super::*, solana_vote_program::vote_state::create_bls_pubkey_and_proof_of_possession};
+    use {
+        super::*,
+        proptest::{collection::vec, prelude::*},
+        solana_vote_program::vote_state::create_bls_pubkey_and_proof_of_possession,
+    };
+
+    fn pubkey() -> impl Strategy<Value = Pubkey> {
+        any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+    }
+
+    fn bytes<const N: usize>() -> impl Strategy<Value = [u8; N]> {
+        vec(any::<u8>(), N).prop_map(|bytes| bytes.try_into().unwrap())
+    }
+
+    fn set_data(
+        new_authorized_voter: &Pubkey,
+        bls_pubkey_compressed: &[u8; BLS_PUBLIC_KEY_COMPRESSED_SIZE],
+        bls_proof_of_possession: &[u8; BLS_PROOF_OF_POSSESSION_COMPRESSED_SIZE],
+    ) -> Vec<u8> {
+        ProgramInstruction::set(
+            &Pubkey::default(),
+            &Pubkey::default(),
+            &Pubkey::default(),
+            new_authorized_voter,
+            bls_pubkey_compressed,
+            bls_proof_of_possession,
+        )
+        .data
+    }
 
     #[test]
     fn test_set_roundtrip() {
@@ -123,17 +160,121 @@ mod tests {
             &bls_proof_of_possession,
         );
 
-        match ProgramInstruction::decode(&ix.data) {
+        match ProgramInstruction::decode(&ix.data).unwrap() {
             ProgramInstruction::Set {
-                new_authorized_voter,
-                bls_pubkey_compressed,
-                bls_proof_of_possession,
+                new_authorized_voter: decoded_voter,
+                bls_pubkey_compressed: decoded_bls_pubkey,
+                bls_proof_of_possession: decoded_bls_pop,
             } => {
-                assert_eq!(new_authorized_voter, new_authorized_voter);
-                assert_eq!(bls_pubkey_compressed, bls_pubkey_compressed);
-                assert_eq!(bls_proof_of_possession, bls_proof_of_possession);
+                assert_eq!(decoded_voter, new_authorized_voter);
+                assert_eq!(decoded_bls_pubkey, bls_pubkey_compressed);
+                assert_eq!(decoded_bls_pop, bls_proof_of_possession);
             }
             _ => panic!("expected Set"),
         }
     }
+
+    #[test]
+    fn test_view_roundtrip() {
+        let ix = ProgramInstruction::view(&Pubkey::new_unique(), &Pubkey::new_unique());
+        assert_eq!(
+            ProgramInstruction::decode(&ix.data),
+            Ok(ProgramInstruction::View)
+        );
+    }
+
+    #[test]
+    fn fail_decode_empty() {
+        assert_eq!(
+            ProgramInstruction::decode(&[]),
+            Err(TestProgramError::EmptyInstructionData.into()),
+        );
+    }
+
+    #[test]
+    fn fail_decode_unknown_tag() {
+        assert_eq!(
+            ProgramInstruction::decode(&[2]),
+            Err(TestProgramError::UnknownInstruction.into()),
+        );
+    }
+
+    #[test]
+    fn fail_decode_truncated() {
+        let vote_account = Pubkey::new_unique();
+        let (bls_pubkey_compressed, bls_proof_of_possession) =
+            create_bls_pubkey_and_proof_of_possession(&vote_account);
+        let ix = ProgramInstruction::set(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &vote_account,
+            &Pubkey::new_unique(),
+            &bls_pubkey_compressed,
+            &bls_proof_of_possession,
+        );
+        assert_eq!(
+            ProgramInstruction::decode(&ix.data[..ix.data.len() - 1]),
+            Err(TestProgramError::TruncatedInstructionData.into()),
+        );
+        assert_eq!(
+            ProgramInstruction::decode(&ix.data[..1]),
+            Err(TestProgramError::TruncatedInstructionData.into()),
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn decode_arbitrary_bytes(input in vec(any::<u8>(), 0..256)) {
+            match ProgramInstruction::decode(&input) {
+                Ok(ProgramInstruction::Set {
+                    new_authorized_voter,
+                    bls_pubkey_compressed,
+                    bls_proof_of_possession,
+                }) => {
+                    let data = set_data(
+                        &new_authorized_voter,
+                        &bls_pubkey_compressed,
+                        &bls_proof_of_possession,
+                    );
+                    prop_assert_eq!(&data, &input[..data.len()]);
+                }
+                Ok(ProgramInstruction::View) => prop_assert_eq!(input[0], 1),
+                Err(err) => prop_assert!([
+                    TestProgramError::EmptyInstructionData.into(),
+                    TestProgramError::TruncatedInstructionData.into(),
+                    TestProgramError::UnknownInstruction.into(),
+                ]
+                .contains(&err)),
+            }
+        }
+
+        #[test]
+        fn set_roundtrip(
+            new_authorized_voter in pubkey(),
+            bls_pubkey_compressed in bytes::<BLS_PUBLIC_KEY_COMPRESSED_SIZE>(),
+            bls_proof_of_possession in bytes::<BLS_PROOF_OF_POSSESSION_COMPRESSED_SIZE>(),
+        ) {
+            prop_assert_eq!(
+                ProgramInstruction::decode(&set_data(
+                    &new_authorized_voter,
+                    &bls_pubkey_compressed,
+                    &bls_proof_of_possession
+                )),
+                Ok(ProgramInstruction::Set {
+                    new_authorized_voter,
+                    bls_pubkey_compressed,
+                    bls_proof_of_possession,
+                })
+            );
+        }
+
+        #[test]
+        fn reject_unknown_tag(tag in 2..=u8::MAX, rest in vec(any::<u8>(), 0..256)) {
+            let input = [&[tag][..], &rest].concat();
+            prop_assert_eq!(
+                ProgramInstruction::decode(&input),
+                Err(TestProgramError::UnknownInstruction.into())
+            );
+        }
+    }
 }
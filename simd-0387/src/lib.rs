@@ -1,4 +1,5 @@
 use {
+    program_error::{check, TestProgramError},
     simd_0387_interface::ProgramInstruction,
     solana_account_info::AccountInfo,
     solana_cpi::invoke,
@@ -23,9 +24,13 @@ fn process_set(
     bls_pubkey_compressed: [u8; BLS_PUBLIC_KEY_COMPRESSED_SIZE],
     bls_proof_of_possession: [u8; BLS_PROOF_OF_POSSESSION_COMPRESSED_SIZE],
 ) -> ProgramResult {
-    let authorized_voter = &accounts[0];
-    let vote_account = &accounts[1];
-    let clock_sysvar = &accounts[2];
+    let [authorized_voter, vote_account, clock_sysvar, vote_program] = check::accounts(accounts)?;
+
+    check::signer(authorized_voter)?;
+    check::writable(vote_account)?;
+    check::owner(vote_account, &solana_sdk_ids::vote::ID)?;
+    check::sysvar(clock_sysvar, &solana_sdk_ids::sysvar::clock::ID)?;
+    check::program(vote_program, &solana_sdk_ids::vote::ID)?;
 
     // CPI to Vote Program: add the BLS key.
     let authorize_ix = authorize(
@@ -34,7 +39,7 @@ fn process_set(
         &new_authorized_voter,
         VoteAuthorize::VoterWithBLS(VoterWithBLSArgs {
             bls_pubkey: bls_pubkey_compressed,
-            bls_proof_of_possession: bls_proof_of_possession,
+            bls_proof_of_possession,
         }),
     );
     invoke(
@@ -50,16 +55,16 @@ fn process_set(
 }
 
 fn process_view(accounts: &[AccountInfo]) -> ProgramResult {
-    let vote_account = &accounts[0];
+    let [vote_account] = check::accounts(accounts)?;
+    check::owner(vote_account, &solana_sdk_ids::vote::ID)?;
     let data = vote_account.try_borrow_data()?;
 
-    let vote_state = match VoteStateVersions::deserialize(&data) {
-        Ok(VoteStateVersions::V4(state)) => state,
-        _ => panic!("expected v4 vote state"),
+    let Ok(VoteStateVersions::V4(vote_state)) = VoteStateVersions::deserialize(&data) else {
+        return Err(TestProgramError::NotV4VoteState.into());
     };
 
     let Some(bls_pubkey) = vote_state.bls_pubkey_compressed else {
-        panic!("expected some bls key");
+        return Err(TestProgramError::MissingBlsKey.into());
     };
 
     msg!("What's the matter, you've never seen a compressed BLS pubkey before?");
@@ -69,7 +74,7 @@ fn process_view(accounts: &[AccountInfo]) -> ProgramResult {
 }
 
 fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
-    match ProgramInstruction::decode(input) {
+    match ProgramInstruction::decode(input)? {
         ProgramInstruction::Set {
             new_authorized_voter,
             bls_pubkey_compressed,
@@ -88,23 +93,42 @@ fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> Progr
 #[cfg(test)]
 mod tests {
     use {
-        mollusk_svm::{program::create_keyed_account_for_builtin_program, result::Check, Mollusk},
+        agave_feature_set::{bls_pubkey_management_in_vote_account, vote_state_v4},
+        feature_matrix::FeatureMatrix,
+        mollusk_svm::{
+            program::create_keyed_account_for_builtin_program,
+            result::{Check, ProgramResult},
+            Mollusk,
+        },
+        program_error::TestProgramError,
         simd_0387_interface::ProgramInstruction,
         solana_account::Account,
         solana_instruction::Instruction,
         solana_program_error::ProgramError,
         solana_pubkey::Pubkey,
         solana_sdk_ids::system_program,
-        solana_vote_interface::{
-            instruction::{create_account_with_config, CreateVoteAccountConfig},
-            state::{
-                VoteInit, VoteStateV4, VoteStateVersions, BLS_PROOF_OF_POSSESSION_COMPRESSED_SIZE,
-                BLS_PUBLIC_KEY_COMPRESSED_SIZE,
-            },
+        solana_vote_interface::state::{
+            VoteStateVersions, BLS_PROOF_OF_POSSESSION_COMPRESSED_SIZE,
+            BLS_PUBLIC_KEY_COMPRESSED_SIZE,
         },
         solana_vote_program::vote_state::create_bls_pubkey_and_proof_of_possession,
+        vote_fixture::VoteAccountFixture,
     };
 
+    /// A vote account with `authorized_voter` as its node, voter and
+    /// withdrawer, as `InitializeAccount` would create it.
+    fn vote_account_fixture(
+        vote_pubkey: &Pubkey,
+        authorized_voter: &Pubkey,
+        fixture: VoteAccountFixture,
+    ) -> VoteAccountFixture {
+        fixture
+            .with_pubkey(*vote_pubkey)
+            .with_node(*authorized_voter)
+            .with_voters(&[(0, *authorized_voter)])
+            .with_withdrawer(*authorized_voter)
+    }
+
     fn setup(
         program_id: &Pubkey,
         authorized_voter: &Pubkey,
@@ -114,51 +138,23 @@ mod tests {
         bls_proof_of_possession: &[u8; BLS_PROOF_OF_POSSESSION_COMPRESSED_SIZE],
         mollusk: &Mollusk,
     ) -> (Instruction, Instruction, Vec<(Pubkey, Account)>) {
-        // First set up the v4 vote account.
-        let payer = Pubkey::new_unique();
-        let space = VoteStateV4::size_of();
-        let lamports = mollusk.sysvars.rent.minimum_balance(space);
-        let pre_result = mollusk.process_instruction_chain(
-            &create_account_with_config(
-                &payer,
-                &vote_pubkey,
-                &VoteInit {
-                    node_pubkey: *authorized_voter,
-                    authorized_voter: *authorized_voter,
-                    authorized_withdrawer: *authorized_voter,
-                    commission: 0,
-                },
-                lamports,
-                CreateVoteAccountConfig {
-                    space: space as u64,
-                    with_seed: None,
-                },
-            ),
-            &[
-                (payer, Account::new(100_000_000, 0, &system_program::id())),
-                (*vote_pubkey, Account::default()),
-                mollusk.sysvars.keyed_account_for_rent_sysvar(),
-                mollusk.sysvars.keyed_account_for_clock_sysvar(),
-                (*authorized_voter, Account::default()),
-            ],
-        );
-
-        let vote_account = &pre_result.resulting_accounts[1].1;
+        let (_, vote_account) =
+            vote_account_fixture(vote_pubkey, authorized_voter, VoteAccountFixture::v4()).build();
 
         let set_ix = ProgramInstruction::set(
             program_id,
-            &authorized_voter,
+            authorized_voter,
             vote_pubkey,
-            &new_authorized_voter,
-            &bls_pubkey_compressed,
-            &bls_proof_of_possession,
+            new_authorized_voter,
+            bls_pubkey_compressed,
+            bls_proof_of_possession,
         );
 
         let view_ix = ProgramInstruction::view(program_id, vote_pubkey);
 
         let accounts = vec![
             (*authorized_voter, Account::default()),
-            (*vote_pubkey, vote_account.clone()),
+            (*vote_pubkey, vote_account),
             mollusk.sysvars.keyed_account_for_clock_sysvar(),
             create_keyed_account_for_builtin_program(&solana_sdk_ids::vote::ID, "vote_program"),
         ];
@@ -243,4 +239,222 @@ mod tests {
             &[Check::err(ProgramError::InvalidInstructionData)],
         );
     }
+
+    #[test]
+    fn test_set_and_view() {
+        let program_id = Pubkey::new_unique();
+        let authorized_voter = Pubkey::new_unique();
+        let vote_pubkey = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0387");
+
+        let (bls_pubkey_compressed, bls_proof_of_possession) =
+            create_bls_pubkey_and_proof_of_possession(&vote_pubkey);
+
+        let (set_ix, view_ix, accounts) = setup(
+            &program_id,
+            &authorized_voter,
+            &vote_pubkey,
+            &Pubkey::new_unique(),
+            &bls_pubkey_compressed,
+            &bls_proof_of_possession,
+            &mollusk,
+        );
+
+        mollusk.process_and_validate_instruction_chain(
+            &[
+                (&set_ix, &[Check::success()]),
+                (&view_ix, &[Check::success()]),
+            ],
+            &accounts,
+        );
+    }
+
+    #[test]
+    fn feature_matrix_set_and_view() {
+        let program_id = Pubkey::new_unique();
+
+        // `Authorize::VoterWithBLS` needs both features.
+        FeatureMatrix::new(&[
+            ("vote_state_v4", vote_state_v4::id()),
+            (
+                "bls_pubkey_management",
+                bls_pubkey_management_in_vote_account::id(),
+            ),
+        ])
+        .run(
+            || Mollusk::new(&program_id, "simd_0387"),
+            |mollusk, combination| {
+                let authorized_voter = Pubkey::new_unique();
+                let vote_pubkey = Pubkey::new_unique();
+                let (bls_pubkey_compressed, bls_proof_of_possession) =
+                    create_bls_pubkey_and_proof_of_possession(&vote_pubkey);
+                let (set_ix, view_ix, mut accounts) = setup(
+                    &program_id,
+                    &authorized_voter,
+                    &vote_pubkey,
+                    &Pubkey::new_unique(),
+                    &bls_pubkey_compressed,
+                    &bls_proof_of_possession,
+                    mollusk,
+                );
+                // The Vote Program creates v3 accounts until `vote_state_v4`
+                // is active.
+                if !combination.is_active(&vote_state_v4::id()) {
+                    let fixture = vote_account_fixture(
+                        &vote_pubkey,
+                        &authorized_voter,
+                        VoteAccountFixture::v3(),
+                    );
+                    accounts[1].1 = fixture.build().1;
+                }
+                mollusk.process_instruction_chain(&[set_ix, view_ix], &accounts)
+            },
+            |combination| {
+                if combination.all_active() {
+                    ProgramResult::Success
+                } else {
+                    ProgramResult::Failure(ProgramError::InvalidInstructionData)
+                }
+            },
+        )
+        .assert_passed();
+    }
+
+    #[test]
+    fn fail_view_missing_bls_key() {
+        let program_id = Pubkey::new_unique();
+        let authorized_voter = Pubkey::new_unique();
+        let vote_pubkey = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0387");
+
+        let (_, view_ix, accounts) = setup(
+            &program_id,
+            &authorized_voter,
+            &vote_pubkey,
+            &Pubkey::new_unique(),
+            &[4u8; BLS_PUBLIC_KEY_COMPRESSED_SIZE],
+            &[4u8; BLS_PROOF_OF_POSSESSION_COMPRESSED_SIZE],
+            &mollusk,
+        );
+
+        // The account is v4, but no BLS key has been set yet.
+        mollusk.process_and_validate_instruction(
+            &view_ix,
+            &accounts,
+            &[Check::err(TestProgramError::MissingBlsKey.into())],
+        );
+    }
+
+    /// Run `set` after `tamper` has broken one of its account constraints,
+    /// and expect it to fail with `err` before any CPI.
+    fn assert_set_err(
+        tamper: impl FnOnce(&mut Instruction, &mut Vec<(Pubkey, Account)>),
+        err: TestProgramError,
+    ) {
+        let program_id = Pubkey::new_unique();
+        let vote_pubkey = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0387");
+        let (bls_pubkey_compressed, bls_proof_of_possession) =
+            create_bls_pubkey_and_proof_of_possession(&vote_pubkey);
+
+        let (mut set_ix, _, mut accounts) = setup(
+            &program_id,
+            &Pubkey::new_unique(),
+            &vote_pubkey,
+            &Pubkey::new_unique(),
+            &bls_pubkey_compressed,
+            &bls_proof_of_possession,
+            &mollusk,
+        );
+        tamper(&mut set_ix, &mut accounts);
+        mollusk.process_and_validate_instruction(&set_ix, &accounts, &[Check::err(err.into())]);
+    }
+
+    /// Swap the account at `index` for an unrelated one.
+    fn replace_account(index: usize) -> impl FnOnce(&mut Instruction, &mut Vec<(Pubkey, Account)>) {
+        move |ix, accounts| {
+            let key = Pubkey::new_unique();
+            ix.accounts[index].pubkey = key;
+            accounts[index] = (key, Account::default());
+        }
+    }
+
+    #[test]
+    fn fail_set_not_enough_accounts() {
+        assert_set_err(
+            |ix, accounts| {
+                ix.accounts.pop();
+                accounts.pop();
+            },
+            TestProgramError::NotEnoughAccounts,
+        );
+    }
+
+    #[test]
+    fn fail_set_authorized_voter_not_signer() {
+        assert_set_err(
+            |ix, _| ix.accounts[0].is_signer = false,
+            TestProgramError::AccountNotSigner,
+        );
+    }
+
+    #[test]
+    fn fail_set_vote_account_not_writable() {
+        assert_set_err(
+            |ix, _| ix.accounts[1].is_writable = false,
+            TestProgramError::AccountNotWritable,
+        );
+    }
+
+    #[test]
+    fn fail_set_vote_account_wrong_owner() {
+        assert_set_err(
+            |_, accounts| accounts[1].1.owner = system_program::ID,
+            TestProgramError::WrongAccountOwner,
+        );
+    }
+
+    #[test]
+    fn fail_set_wrong_clock_sysvar() {
+        assert_set_err(replace_account(2), TestProgramError::InvalidSysvar);
+    }
+
+    #[test]
+    fn fail_set_wrong_vote_program() {
+        assert_set_err(replace_account(3), TestProgramError::InvalidProgramAccount);
+    }
+
+    fn assert_decode_err(data: &[u8], err: TestProgramError) {
+        let program_id = Pubkey::new_unique();
+        let mollusk = Mollusk::new(&program_id, "simd_0387");
+        let instruction = Instruction::new_with_bytes(program_id, data, vec![]);
+        mollusk.process_and_validate_instruction(&instruction, &[], &[Check::err(err.into())]);
+    }
+
+    #[test]
+    fn fail_empty_instruction_data() {
+        assert_decode_err(&[], TestProgramError::EmptyInstructionData);
+    }
+
+    #[test]
+    fn fail_unknown_instruction() {
+        assert_decode_err(&[2], TestProgramError::UnknownInstruction);
+    }
+
+    #[test]
+    fn fail_truncated_instruction_data() {
+        let ix = ProgramInstruction::set(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &[4u8; BLS_PUBLIC_KEY_COMPRESSED_SIZE],
+            &[4u8; BLS_PROOF_OF_POSSESSION_COMPRESSED_SIZE],
+        );
+        let data = ix.data;
+        assert_decode_err(
+            &data[..data.len() - 1],
+            TestProgramError::TruncatedInstructionData,
+        );
+    }
 }
@@ -0,0 +1,377 @@
+//! A local validator for end-to-end tests.
+//!
+//! Spawns `solana-test-validator` (or `agave-test-validator`) on free ports
+//! with the workspace's `simd-*` programs preloaded from `target/deploy` and
+//! any features deactivated, waits until it is ready, and hands back an
+//! `RpcClient` and a payer funded as the genesis mint:
+//!
+//! ```ignore
+//! let validator = TestValidator::builder()
+//!     .program("simd-0185")
+//!     .deactivate_feature(agave_feature_set::vote_state_v4::ID)
+//!     .start()
+//!     .unwrap();
+//! let program_id = validator.program_id("simd-0185");
+//! validator.client().get_balance(&validator.payer().pubkey()).unwrap();
+//! ```
+//!
+//! The validator is killed and its ledger deleted when the `TestValidator` is
+//! dropped. Set `TEST_VALIDATOR` to the path of the binary to use a specific
+//! build.
+
+use {
+    helpers::{deployments::local_elf_path, CommitmentConfig, Keypair, RpcClient, Signer},
+    solana_pubkey::Pubkey,
+    std::{
+        env,
+        net::TcpListener,
+        path::{Path, PathBuf},
+        process::{Child, Command, Stdio},
+        thread,
+        time::{Duration, Instant},
+    },
+    tempfile::TempDir,
+};
+
+/// Validator binaries to look for on `PATH`, in order.
+const VALIDATOR_BINARIES: &[&str] = &["solana-test-validator", "agave-test-validator"];
+
+/// Environment variable naming the validator binary, overriding `PATH`.
+const VALIDATOR_ENV_VAR: &str = "TEST_VALIDATOR";
+
+const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Configures and starts a `TestValidator`.
+#[derive(Clone, Debug)]
+pub struct TestValidatorBuilder {
+    /// Workspace programs (e.g. `simd-0185`) and the addresses to load them
+    /// at.
+    programs: Vec<(String, Pubkey)>,
+    deactivated_features: Vec<Pubkey>,
+    startup_timeout: Duration,
+}
+
+impl Default for TestValidatorBuilder {
+    fn default() -> Self {
+        Self {
+            programs: Vec::new(),
+            deactivated_features: Vec::new(),
+            startup_timeout: DEFAULT_STARTUP_TIMEOUT,
+        }
+    }
+}
+
+impl TestValidatorBuilder {
+    /// Load `program` (e.g. `simd-0185`) from `target/deploy` at a fresh
+    /// address. See `TestValidator::program_id`.
+    pub fn program(self, program: &str) -> Self {
+        self.program_with_id(program, Pubkey::new_unique())
+    }
+
+    /// Load `program` (e.g. `simd-0185`) from `target/deploy` at
+    /// `program_id`.
+    pub fn program_with_id(mut self, program: &str, program_id: Pubkey) -> Self {
+        self.programs.push((program.to_string(), program_id));
+        self
+    }
+
+    /// Start the validator with `feature_id` inactive.
+    pub fn deactivate_feature(mut self, feature_id: Pubkey) -> Self {
+        self.deactivated_features.push(feature_id);
+        self
+    }
+
+    /// How long to wait for the validator to become ready.
+    pub fn startup_timeout(mut self, startup_timeout: Duration) -> Self {
+        self.startup_timeout = startup_timeout;
+        self
+    }
+
+    /// Spawn the validator and wait until it is ready.
+    pub fn start(self) -> Result<TestValidator, String> {
+        for (program, _) in &self.programs {
+            let elf_path = local_elf_path(program);
+            if !elf_path.exists() {
+                return Err(format!(
+                    "{} not found; run `make build-{program}` first",
+                    elf_path.display()
+                ));
+            }
+        }
+
+        let binary = validator_binary()?;
+        let ledger = TempDir::new().map_err(|err| format!("failed to create ledger dir: {err}"))?;
+        let payer = Keypair::new();
+        let ports = Ports::free()?;
+
+        let process = Command::new(&binary)
+            .args(self.args(ledger.path(), &ports, &payer.pubkey()))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| format!("failed to spawn {}: {err}", binary.display()))?;
+
+        let rpc_url = format!("http://127.0.0.1:{}", ports.rpc);
+        let mut validator = TestValidator {
+            process,
+            ledger: Some(ledger),
+            client: RpcClient::new_with_commitment(rpc_url.clone(), CommitmentConfig::confirmed()),
+            rpc_url,
+            payer,
+            programs: self.programs,
+        };
+        if let Err(err) = validator.wait_until_ready(self.startup_timeout) {
+            // Keep the ledger around so its log can be inspected.
+            let ledger = validator.ledger.take().map(TempDir::keep);
+            let log = ledger.unwrap_or_default().join("validator.log");
+            return Err(format!("{err}; see {}", log.display()));
+        }
+        Ok(validator)
+    }
+
+    fn args(&self, ledger: &Path, ports: &Ports, mint: &Pubkey) -> Vec<String> {
+        let mut args = vec![
+            "--ledger".to_string(),
+            ledger.display().to_string(),
+            "--reset".to_string(),
+            "--quiet".to_string(),
+            "--rpc-port".to_string(),
+            ports.rpc.to_string(),
+            "--faucet-port".to_string(),
+            ports.faucet.to_string(),
+            "--gossip-port".to_string(),
+            ports.gossip.to_string(),
+            "--dynamic-port-range".to_string(),
+            format!("{}-{}", ports.dynamic.0, ports.dynamic.1),
+            "--mint".to_string(),
+            mint.to_string(),
+        ];
+        for (program, program_id) in &self.programs {
+            args.push("--bpf-program".to_string());
+            args.push(program_id.to_string());
+            args.push(local_elf_path(program).display().to_string());
+        }
+        for feature_id in &self.deactivated_features {
+            args.push("--deactivate-feature".to_string());
+            args.push(feature_id.to_string());
+        }
+        args
+    }
+}
+
+/// A running local validator. Killed when dropped.
+pub struct TestValidator {
+    process: Child,
+    /// `None` once startup has failed and the ledger has been kept.
+    ledger: Option<TempDir>,
+    rpc_url: String,
+    client: RpcClient,
+    payer: Keypair,
+    programs: Vec<(String, Pubkey)>,
+}
+
+impl TestValidator {
+    pub fn builder() -> TestValidatorBuilder {
+        TestValidatorBuilder::default()
+    }
+
+    pub fn rpc_url(&self) -> &str {
+        &self.rpc_url
+    }
+
+    pub fn client(&self) -> &RpcClient {
+        &self.client
+    }
+
+    /// The genesis mint, funded with the validator's default mint balance.
+    pub fn payer(&self) -> &Keypair {
+        &self.payer
+    }
+
+    /// The address `program` (e.g. `simd-0185`) was loaded at.
+    pub fn program_id(&self, program: &str) -> Pubkey {
+        self.programs
+            .iter()
+            .find(|(name, _)| name == program)
+            .map(|(_, program_id)| *program_id)
+            .unwrap_or_else(|| panic!("{program} was not loaded"))
+    }
+
+    fn wait_until_ready(&mut self, timeout: Duration) -> Result<(), String> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(status) = self
+                .process
+                .try_wait()
+                .map_err(|err| format!("failed to poll validator: {err}"))?
+            {
+                return Err(format!("validator exited during startup ({status})"));
+            }
+            // A blockhash is available once the first slot has been
+            // processed, so transactions can be sent straight away.
+            if self.client.get_health().is_ok() && self.client.get_latest_blockhash().is_ok() {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(format!("validator not ready after {}s", timeout.as_secs()));
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+impl Drop for TestValidator {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+/// `$TEST_VALIDATOR`, or the first of `VALIDATOR_BINARIES` on `PATH`.
+fn validator_binary() -> Result<PathBuf, String> {
+    if let Some(binary) = env::var_os(VALIDATOR_ENV_VAR) {
+        return Ok(PathBuf::from(binary));
+    }
+    let path = env::var_os("PATH").unwrap_or_default();
+    VALIDATOR_BINARIES
+        .iter()
+        .find_map(|binary| {
+            env::split_paths(&path)
+                .map(|dir| dir.join(binary))
+                .find(|candidate| candidate.is_file())
+        })
+        .ok_or_else(|| {
+            format!(
+                "none of {} found on PATH; install the Solana CLI or set ${VALIDATOR_ENV_VAR}",
+                VALIDATOR_BINARIES.join(", ")
+            )
+        })
+}
+
+/// The width of each validator's dynamic port range, the minimum the
+/// validator accepts.
+const DYNAMIC_PORT_RANGE_WIDTH: u16 = 25;
+
+/// The ports a validator listens on, free when it is started so that
+/// validators can run in parallel.
+struct Ports {
+    /// The RPC port; PubSub uses the next one.
+    rpc: u16,
+    faucet: u16,
+    gossip: u16,
+    /// Start and end of the range the other services bind in.
+    dynamic: (u16, u16),
+}
+
+impl Ports {
+    fn free() -> Result<Self, String> {
+        // Hold every port until all are chosen, so none is chosen twice.
+        let mut reserved = Vec::new();
+        let rpc = reserve_ports(2, &mut reserved)?;
+        let faucet = reserve_ports(1, &mut reserved)?;
+        let gossip = reserve_ports(1, &mut reserved)?;
+        let dynamic = reserve_ports(DYNAMIC_PORT_RANGE_WIDTH, &mut reserved)?;
+        Ok(Self {
+            rpc,
+            faucet,
+            gossip,
+            dynamic: (dynamic, dynamic + DYNAMIC_PORT_RANGE_WIDTH),
+        })
+    }
+}
+
+/// Find `count` consecutive free ports, adding their listeners to `reserved`,
+/// and return the first.
+fn reserve_ports(count: u16, reserved: &mut Vec<TcpListener>) -> Result<u16, String> {
+    for _ in 0..16 {
+        let first = TcpListener::bind("127.0.0.1:0")
+            .map_err(|err| format!("failed to find a free port: {err}"))?;
+        let port = first
+            .local_addr()
+            .map_err(|err| format!("failed to find a free port: {err}"))?
+            .port();
+        let Some(last) = port.checked_add(count - 1) else {
+            continue;
+        };
+        let rest = (port + 1..=last)
+            .map(|port| TcpListener::bind(("127.0.0.1", port)))
+            .collect::<Result<Vec<_>, _>>();
+        if let Ok(rest) = rest {
+            reserved.push(first);
+            reserved.extend(rest);
+            return Ok(port);
+        }
+    }
+    Err(format!("failed to find {count} consecutive free ports"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, helpers::feature::get_feature_status};
+
+    #[test]
+    fn validator_args() {
+        let program_id = Pubkey::new_unique();
+        let feature_id = agave_feature_set::vote_state_v4::ID;
+        let mint = Pubkey::new_unique();
+        let args = TestValidator::builder()
+            .program_with_id("simd-0185", program_id)
+            .deactivate_feature(feature_id)
+            .args(
+                Path::new("/tmp/ledger"),
+                &Ports {
+                    rpc: 8899,
+                    faucet: 9900,
+                    gossip: 8001,
+                    dynamic: (8002, 8027),
+                },
+                &mint,
+            )
+            .join(" ");
+
+        assert!(args.starts_with("--ledger /tmp/ledger --reset --quiet"));
+        assert!(args.contains(
+            "--rpc-port 8899 --faucet-port 9900 --gossip-port 8001 --dynamic-port-range 8002-8027"
+        ));
+        assert!(args.contains(&format!("--mint {mint}")));
+        assert!(args.contains(&format!(
+            "--bpf-program {program_id} {}",
+            local_elf_path("simd-0185").display()
+        )));
+        assert!(args.ends_with(&format!("--deactivate-feature {feature_id}")));
+    }
+
+    #[test]
+    fn free_ports_dont_overlap() {
+        let ports = Ports::free().unwrap();
+        let mut all = vec![ports.rpc, ports.rpc + 1, ports.faucet, ports.gossip];
+        all.extend(ports.dynamic.0..ports.dynamic.1);
+        let count = all.len();
+        all.sort();
+        all.dedup();
+        assert_eq!(all.len(), count);
+        assert_eq!(ports.dynamic.1 - ports.dynamic.0, DYNAMIC_PORT_RANGE_WIDTH);
+    }
+
+    #[test]
+    #[ignore = "requires solana-test-validator and `make build-simd-0321`"]
+    fn start_with_program_and_deactivated_feature() {
+        let feature_id = agave_feature_set::provide_instruction_data_offset_in_vm_r2::ID;
+        let validator = TestValidator::builder()
+            .program("simd-0321")
+            .deactivate_feature(feature_id)
+            .start()
+            .unwrap();
+        let client = validator.client();
+
+        let program = client
+            .get_account(&validator.program_id("simd-0321"))
+            .unwrap();
+        assert!(program.executable);
+        assert!(client.get_balance(&validator.payer().pubkey()).unwrap() > 0);
+        assert!(!get_feature_status(client, &feature_id).is_active());
+    }
+}
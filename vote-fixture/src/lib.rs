@@ -0,0 +1,303 @@
+//! Vote account fixtures for mollusk tests.
+//!
+//! Builds `(Pubkey, Account)` pairs holding a vote state in the v1_14_11, v3
+//! or v4 layout directly, so tests can start from any vote state without
+//! running the Vote Program:
+//!
+//! ```ignore
+//! let (vote_pubkey, vote_account) = VoteAccountFixture::v4()
+//!     .with_bls_key([4; BLS_PUBLIC_KEY_COMPRESSED_SIZE])
+//!     .with_commission_bps(500)
+//!     .with_voters(&[(0, voter)])
+//!     .build();
+//! ```
+//!
+//! Unset fields match what `InitializeAccount` would write: fresh node,
+//! voter and withdrawer keys, and in v4 the SIMD-0185 default collectors and
+//! a 100% block revenue commission.
+
+use {
+    solana_account::Account,
+    solana_pubkey::Pubkey,
+    solana_rent::Rent,
+    solana_vote_interface::{
+        authorized_voters::AuthorizedVoters,
+        state::{
+            VoteState1_14_11, VoteStateV3, VoteStateV4, VoteStateVersions,
+            BLS_PUBLIC_KEY_COMPRESSED_SIZE,
+        },
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Layout {
+    V1_14_11,
+    V3,
+    V4,
+}
+
+/// Builds a vote account. See the crate docs.
+#[derive(Clone, Debug)]
+pub struct VoteAccountFixture {
+    layout: Layout,
+    pubkey: Pubkey,
+    node_pubkey: Pubkey,
+    authorized_withdrawer: Pubkey,
+    /// `(epoch, authorized voter)` pairs.
+    voters: Vec<(u64, Pubkey)>,
+    commission_bps: u16,
+    block_revenue_commission_bps: u16,
+    /// `None` for the SIMD-0185 defaults.
+    collectors: Option<(Pubkey, Pubkey)>,
+    bls_pubkey_compressed: Option<[u8; BLS_PUBLIC_KEY_COMPRESSED_SIZE]>,
+    /// `None` for the rent-exempt minimum.
+    lamports: Option<u64>,
+}
+
+impl VoteAccountFixture {
+    fn new(layout: Layout) -> Self {
+        Self {
+            layout,
+            pubkey: Pubkey::new_unique(),
+            node_pubkey: Pubkey::new_unique(),
+            authorized_withdrawer: Pubkey::new_unique(),
+            voters: vec![(0, Pubkey::new_unique())],
+            commission_bps: 0,
+            block_revenue_commission_bps: 10_000,
+            collectors: None,
+            bls_pubkey_compressed: None,
+            lamports: None,
+        }
+    }
+
+    pub fn v1_14_11() -> Self {
+        Self::new(Layout::V1_14_11)
+    }
+
+    pub fn v3() -> Self {
+        Self::new(Layout::V3)
+    }
+
+    pub fn v4() -> Self {
+        Self::new(Layout::V4)
+    }
+
+    pub fn with_pubkey(mut self, pubkey: Pubkey) -> Self {
+        self.pubkey = pubkey;
+        self
+    }
+
+    pub fn with_node(mut self, node_pubkey: Pubkey) -> Self {
+        self.node_pubkey = node_pubkey;
+        self
+    }
+
+    pub fn with_withdrawer(mut self, authorized_withdrawer: Pubkey) -> Self {
+        self.authorized_withdrawer = authorized_withdrawer;
+        self
+    }
+
+    /// Replace the authorized voters with `(epoch, voter)` pairs.
+    pub fn with_voters(mut self, voters: &[(u64, Pubkey)]) -> Self {
+        assert!(
+            !voters.is_empty(),
+            "a vote account needs an authorized voter"
+        );
+        self.voters = voters.to_vec();
+        self
+    }
+
+    /// The inflation rewards commission. Pre-v4 layouts store a percentage,
+    /// so `commission_bps` must be a multiple of 100 for them.
+    pub fn with_commission_bps(mut self, commission_bps: u16) -> Self {
+        assert!(commission_bps <= 10_000, "commission is at most 10,000 bps");
+        self.commission_bps = commission_bps;
+        self
+    }
+
+    /// v4 only.
+    pub fn with_block_revenue_commission_bps(mut self, commission_bps: u16) -> Self {
+        assert!(commission_bps <= 10_000, "commission is at most 10,000 bps");
+        self.block_revenue_commission_bps = commission_bps;
+        self
+    }
+
+    /// v4 only.
+    pub fn with_collectors(
+        mut self,
+        inflation_rewards_collector: Pubkey,
+        block_revenue_collector: Pubkey,
+    ) -> Self {
+        self.collectors = Some((inflation_rewards_collector, block_revenue_collector));
+        self
+    }
+
+    /// v4 only.
+    pub fn with_bls_key(
+        mut self,
+        bls_pubkey_compressed: [u8; BLS_PUBLIC_KEY_COMPRESSED_SIZE],
+    ) -> Self {
+        self.bls_pubkey_compressed = Some(bls_pubkey_compressed);
+        self
+    }
+
+    pub fn with_lamports(mut self, lamports: u64) -> Self {
+        self.lamports = Some(lamports);
+        self
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    /// The vote state this fixture serializes.
+    pub fn state(&self) -> VoteStateVersions {
+        let mut authorized_voters = AuthorizedVoters::default();
+        for (epoch, voter) in &self.voters {
+            authorized_voters.insert(*epoch, *voter);
+        }
+
+        if self.layout != Layout::V4 {
+            assert!(
+                self.commission_bps.is_multiple_of(100),
+                "pre-v4 commission is a whole percentage, got {} bps",
+                self.commission_bps
+            );
+            assert!(
+                self.collectors.is_none()
+                    && self.bls_pubkey_compressed.is_none()
+                    && self.block_revenue_commission_bps == 10_000,
+                "collectors, block revenue commission and BLS keys are v4 only"
+            );
+        }
+
+        let v3 = VoteStateV3 {
+            node_pubkey: self.node_pubkey,
+            authorized_withdrawer: self.authorized_withdrawer,
+            commission: (self.commission_bps / 100) as u8,
+            authorized_voters: authorized_voters.clone(),
+            ..VoteStateV3::default()
+        };
+        match self.layout {
+            Layout::V1_14_11 => VoteStateVersions::V1_14_11(Box::new(VoteState1_14_11::from(v3))),
+            Layout::V3 => VoteStateVersions::new_v3(v3),
+            Layout::V4 => {
+                let (inflation_rewards_collector, block_revenue_collector) =
+                    self.collectors.unwrap_or((self.pubkey, self.node_pubkey));
+                VoteStateVersions::new_v4(VoteStateV4 {
+                    node_pubkey: self.node_pubkey,
+                    authorized_withdrawer: self.authorized_withdrawer,
+                    inflation_rewards_collector,
+                    block_revenue_collector,
+                    inflation_rewards_commission_bps: self.commission_bps,
+                    block_revenue_commission_bps: self.block_revenue_commission_bps,
+                    bls_pubkey_compressed: self.bls_pubkey_compressed,
+                    authorized_voters,
+                    ..VoteStateV4::default()
+                })
+            }
+        }
+    }
+
+    /// The account's data length for its layout.
+    pub fn space(&self) -> usize {
+        match self.layout {
+            Layout::V1_14_11 => VoteState1_14_11::size_of(),
+            Layout::V3 => VoteStateV3::size_of(),
+            Layout::V4 => VoteStateV4::size_of(),
+        }
+    }
+
+    pub fn build(&self) -> (Pubkey, Account) {
+        let space = self.space();
+        let mut account = Account::new(
+            self.lamports
+                .unwrap_or_else(|| Rent::default().minimum_balance(space)),
+            space,
+            &solana_sdk_ids::vote::ID,
+        );
+        VoteStateV4::serialize(&self.state(), &mut account.data)
+            .expect("vote state fits its layout");
+        (self.pubkey, account)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deserialize(account: &Account) -> VoteStateVersions {
+        VoteStateVersions::deserialize(&account.data).unwrap()
+    }
+
+    #[test]
+    fn v4_fixture() {
+        let voter = Pubkey::new_unique();
+        let (pubkey, account) = VoteAccountFixture::v4()
+            .with_bls_key([4; BLS_PUBLIC_KEY_COMPRESSED_SIZE])
+            .with_commission_bps(550)
+            .with_voters(&[(0, Pubkey::new_unique()), (1, voter)])
+            .build();
+
+        assert_eq!(account.owner, solana_sdk_ids::vote::ID);
+        assert_eq!(account.data.len(), VoteStateV4::size_of());
+        assert_eq!(
+            account.lamports,
+            Rent::default().minimum_balance(account.data.len())
+        );
+
+        let VoteStateVersions::V4(state) = deserialize(&account) else {
+            panic!("expected v4 vote state");
+        };
+        assert_eq!(
+            state.bls_pubkey_compressed,
+            Some([4; BLS_PUBLIC_KEY_COMPRESSED_SIZE])
+        );
+        assert_eq!(state.inflation_rewards_commission_bps, 550);
+        assert_eq!(state.authorized_voters.last(), Some((&1, &voter)));
+        assert_eq!(state.inflation_rewards_collector, pubkey);
+        assert_eq!(state.block_revenue_collector, state.node_pubkey);
+        assert_eq!(state.block_revenue_commission_bps, 10_000);
+    }
+
+    #[test]
+    fn pre_v4_fixtures() {
+        let node = Pubkey::new_unique();
+        let fixture = VoteAccountFixture::v3()
+            .with_node(node)
+            .with_commission_bps(1_000)
+            .with_lamports(1);
+
+        let (_, account) = fixture.build();
+        assert_eq!(account.lamports, 1);
+        assert_eq!(account.data.len(), VoteStateV3::size_of());
+        let VoteStateVersions::V3(state) = deserialize(&account) else {
+            panic!("expected v3 vote state");
+        };
+        assert_eq!((state.node_pubkey, state.commission), (node, 10));
+
+        let (_, account) = VoteAccountFixture::v1_14_11()
+            .with_node(node)
+            .with_commission_bps(1_000)
+            .build();
+        assert_eq!(account.data.len(), VoteState1_14_11::size_of());
+        let VoteStateVersions::V1_14_11(state) = deserialize(&account) else {
+            panic!("expected v1_14_11 vote state");
+        };
+        assert_eq!((state.node_pubkey, state.commission), (node, 10));
+    }
+
+    #[test]
+    #[should_panic(expected = "pre-v4 commission is a whole percentage")]
+    fn pre_v4_fractional_commission() {
+        VoteAccountFixture::v3().with_commission_bps(550).build();
+    }
+
+    #[test]
+    #[should_panic(expected = "v4 only")]
+    fn pre_v4_bls_key() {
+        VoteAccountFixture::v1_14_11()
+            .with_bls_key([4; BLS_PUBLIC_KEY_COMPRESSED_SIZE])
+            .build();
+    }
+}